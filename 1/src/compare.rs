@@ -0,0 +1,416 @@
+//! Comparing lists of location ids.
+//!
+//! Values are kept as `i128` so any mix of negative numbers and `u64` ids fits.
+//! Every comparison keeps the per-pair contributions next to the total, which
+//! makes it possible to tell which pairs dominate the result.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    /// Smallest with smallest, second smallest with second smallest, ...
+    Sorted,
+    /// Pairs the values in input order.
+    Positional,
+    /// The assignment with the lowest total cost under the metric.
+    Optimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Sum of absolute differences.
+    L1,
+    /// Sum of squared differences (the squared Euclidean distance, kept exact).
+    L2,
+    /// Each left value multiplied by how often it appears on the right.
+    Similarity,
+    /// Number of values the two lists have in common, counting duplicates.
+    Intersection,
+}
+
+impl Metric {
+    fn pair_cost(&self, left: i128, right: i128) -> Result<i128, String> {
+        let diff = left
+            .checked_sub(right)
+            .ok_or_else(|| format!("Overflow in {left} - {right}"))?;
+        match self {
+            Metric::L1 => diff
+                .checked_abs()
+                .ok_or_else(|| format!("Overflow in |{diff}|")),
+            Metric::L2 => diff
+                .checked_mul(diff)
+                .ok_or_else(|| format!("Overflow in {diff}^2")),
+            Metric::Similarity | Metric::Intersection => {
+                unreachable!("{self:?} is not a pairwise metric")
+            }
+        }
+    }
+}
+
+/// What a single pair (or value, for the counting metrics) adds to the total.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contribution {
+    pub left: i128,
+    pub right: i128,
+    /// 1 for pairs, the number of matches for the counting metrics.
+    pub multiplicity: usize,
+    pub value: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub metric: Metric,
+    pub contributions: Vec<Contribution>,
+    pub total: i128,
+}
+
+impl Comparison {
+    /// The `n` contributions with the largest values, largest first.
+    pub fn dominant(&self, n: usize) -> Vec<&Contribution> {
+        let mut contributions = self.contributions.iter().collect::<Vec<_>>();
+        contributions.sort_by_key(|contribution| std::cmp::Reverse(contribution.value));
+        contributions.truncate(n);
+        contributions
+    }
+}
+
+/// Parses whitespace separated columns, one row per line. Blank lines are
+/// skipped, all other lines must have the same number of columns.
+pub fn parse_columns(input: &str) -> Result<Vec<Vec<i128>>, String> {
+    let mut columns: Vec<Vec<i128>> = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let values = line
+            .split_whitespace()
+            .map(|value| {
+                value
+                    .parse::<i128>()
+                    .map_err(|e| format!("Invalid number {value:?} on line {}: {e}", index + 1))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.is_empty() {
+            continue;
+        }
+
+        if columns.is_empty() {
+            columns = vec![Vec::new(); values.len()];
+        } else if values.len() != columns.len() {
+            return Err(format!(
+                "Line {} has {} columns, expected {}",
+                index + 1,
+                values.len(),
+                columns.len()
+            ));
+        }
+        for (column, value) in columns.iter_mut().zip(values) {
+            column.push(value);
+        }
+    }
+
+    Ok(columns)
+}
+
+pub fn compare(
+    left: &[i128],
+    right: &[i128],
+    pairing: Pairing,
+    metric: Metric,
+) -> Result<Comparison, String> {
+    let contributions = match metric {
+        Metric::L1 | Metric::L2 => pairwise(left, right, pairing, metric)?,
+        Metric::Similarity => similarity(left, right)?,
+        Metric::Intersection => intersection(left, right),
+    };
+    let total = contributions
+        .iter()
+        .try_fold(0i128, |total, contribution| {
+            total
+                .checked_add(contribution.value)
+                .ok_or_else(|| "Overflow while summing contributions".to_string())
+        })?;
+
+    Ok(Comparison {
+        metric,
+        contributions,
+        total,
+    })
+}
+
+/// Comparison of the columns `(i, j)`.
+pub type ColumnComparison = ((usize, usize), Comparison);
+
+/// Compares every pair of columns `(i, j)` with `i < j`.
+pub fn compare_columns(
+    columns: &[Vec<i128>],
+    pairing: Pairing,
+    metric: Metric,
+) -> Result<Vec<ColumnComparison>, String> {
+    let mut comparisons = Vec::new();
+    for i in 0..columns.len() {
+        for j in i + 1..columns.len() {
+            let comparison = compare(&columns[i], &columns[j], pairing, metric)?;
+            comparisons.push(((i, j), comparison));
+        }
+    }
+    Ok(comparisons)
+}
+
+fn pairwise(
+    left: &[i128],
+    right: &[i128],
+    pairing: Pairing,
+    metric: Metric,
+) -> Result<Vec<Contribution>, String> {
+    if left.len() != right.len() {
+        return Err(format!(
+            "Cannot pair lists of {} and {} values",
+            left.len(),
+            right.len()
+        ));
+    }
+
+    let pairs = match pairing {
+        Pairing::Positional => left.iter().copied().zip(right.iter().copied()).collect(),
+        Pairing::Sorted => {
+            let mut left = left.to_vec();
+            let mut right = right.to_vec();
+            left.sort_unstable();
+            right.sort_unstable();
+            left.into_iter().zip(right).collect()
+        }
+        Pairing::Optimal => {
+            let costs = left
+                .iter()
+                .map(|&l| {
+                    right
+                        .iter()
+                        .map(|&r| metric.pair_cost(l, r))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            optimal_assignment(&costs)?
+                .into_iter()
+                .enumerate()
+                .map(|(l, r)| (left[l], right[r]))
+                .collect::<Vec<_>>()
+        }
+    };
+
+    pairs
+        .into_iter()
+        .map(|(left, right)| {
+            Ok(Contribution {
+                left,
+                right,
+                multiplicity: 1,
+                value: metric.pair_cost(left, right)?,
+            })
+        })
+        .collect()
+}
+
+fn counts(values: &[i128]) -> HashMap<i128, usize> {
+    let mut counts = HashMap::new();
+    for &value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn similarity(left: &[i128], right: &[i128]) -> Result<Vec<Contribution>, String> {
+    let counts = counts(right);
+    left.iter()
+        .map(|&value| {
+            let multiplicity = counts.get(&value).copied().unwrap_or(0);
+            let score = value
+                .checked_mul(multiplicity as i128)
+                .ok_or_else(|| format!("Overflow in {value} * {multiplicity}"))?;
+            Ok(Contribution {
+                left: value,
+                right: value,
+                multiplicity,
+                value: score,
+            })
+        })
+        .collect()
+}
+
+fn intersection(left: &[i128], right: &[i128]) -> Vec<Contribution> {
+    let right_counts = counts(right);
+    let mut common = counts(left)
+        .into_iter()
+        .filter_map(|(value, left_count)| {
+            let multiplicity = left_count.min(*right_counts.get(&value)?);
+            Some(Contribution {
+                left: value,
+                right: value,
+                multiplicity,
+                value: multiplicity as i128,
+            })
+        })
+        .collect::<Vec<_>>();
+    common.sort_by_key(|contribution| contribution.left);
+    common
+}
+
+/// Hungarian algorithm on a square cost matrix. Returns, for every row, the
+/// column it is assigned to.
+fn optimal_assignment(costs: &[Vec<i128>]) -> Result<Vec<usize>, String> {
+    let n = costs.len();
+    let overflow = || "Overflow in the optimal assignment".to_string();
+    // Potentials and matching are 1-indexed, index 0 is a virtual column.
+    let mut u = vec![0i128; n + 1];
+    let mut v = vec![0i128; n + 1];
+    let mut row_of = vec![0usize; n + 1];
+    let mut way = vec![0usize; n + 1];
+
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut column = 0;
+        // `None` until a row has been tried against the column.
+        let mut min_slack: Vec<Option<i128>> = vec![None; n + 1];
+        let mut used = vec![false; n + 1];
+
+        loop {
+            used[column] = true;
+            let current_row = row_of[column];
+            let mut delta: Option<i128> = None;
+            let mut next_column = 0;
+
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+                let slack = costs[current_row - 1][j - 1]
+                    .checked_sub(u[current_row])
+                    .and_then(|slack| slack.checked_sub(v[j]))
+                    .ok_or_else(overflow)?;
+                if min_slack[j].is_none_or(|min| slack < min) {
+                    min_slack[j] = Some(slack);
+                    way[j] = column;
+                }
+                let slack = min_slack[j].unwrap_or(slack);
+                if delta.is_none_or(|delta| slack < delta) {
+                    delta = Some(slack);
+                    next_column = j;
+                }
+            }
+            let delta = delta.expect("an unused column is left while a row is unassigned");
+
+            for j in 0..=n {
+                if used[j] {
+                    u[row_of[j]] = u[row_of[j]].checked_add(delta).ok_or_else(overflow)?;
+                    v[j] = v[j].checked_sub(delta).ok_or_else(overflow)?;
+                } else if let Some(slack) = min_slack[j] {
+                    min_slack[j] = Some(slack.checked_sub(delta).ok_or_else(overflow)?);
+                }
+            }
+
+            column = next_column;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+
+        while column != 0 {
+            let previous = way[column];
+            row_of[column] = row_of[previous];
+            column = previous;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for column in 1..=n {
+        assignment[row_of[column] - 1] = column - 1;
+    }
+    Ok(assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEFT: [i128; 6] = [3, 4, 2, 1, 3, 3];
+    const RIGHT: [i128; 6] = [4, 3, 5, 3, 9, 3];
+
+    #[test]
+    fn test_parse_columns() {
+        let columns = parse_columns("1 -2 18446744073709551615\n\n4 5 6\n").unwrap();
+        assert_eq!(
+            columns,
+            vec![vec![1, 4], vec![-2, 5], vec![u64::MAX as i128, 6]]
+        );
+        assert!(parse_columns("1 2\n3\n").is_err());
+        assert!(parse_columns("1 x\n").is_err());
+    }
+
+    #[test]
+    fn test_metrics_on_example() {
+        let total = |pairing, metric| compare(&LEFT, &RIGHT, pairing, metric).unwrap().total;
+        assert_eq!(total(Pairing::Sorted, Metric::L1), 11);
+        assert_eq!(total(Pairing::Positional, Metric::L1), 1 + 1 + 3 + 2 + 6);
+        assert_eq!(total(Pairing::Sorted, Metric::L2), 35);
+        assert_eq!(total(Pairing::Sorted, Metric::Similarity), 31);
+        assert_eq!(total(Pairing::Sorted, Metric::Intersection), 4);
+    }
+
+    #[test]
+    fn test_optimal_matches_sorted_for_l1_and_l2() {
+        let left = [7, -3, 12, 0, 5, 5, -8, 21];
+        let right = [1, 14, -2, 6, 6, 30, -9, 3];
+        for metric in [Metric::L1, Metric::L2] {
+            let sorted = compare(&left, &right, Pairing::Sorted, metric).unwrap();
+            let optimal = compare(&left, &right, Pairing::Optimal, metric).unwrap();
+            assert_eq!(optimal.total, sorted.total);
+        }
+    }
+
+    #[test]
+    fn test_optimal_assignment() {
+        let costs = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(optimal_assignment(&costs), Ok(vec![1, 0, 2]));
+    }
+
+    #[test]
+    fn test_pairwise_requires_equal_lengths() {
+        assert!(compare(&[1, 2], &[1], Pairing::Sorted, Metric::L1).is_err());
+        assert!(compare(&[1, 2], &[1], Pairing::Sorted, Metric::Similarity).is_ok());
+    }
+
+    #[test]
+    fn test_dominant_contributions() {
+        let comparison = compare(&LEFT, &RIGHT, Pairing::Sorted, Metric::L1).unwrap();
+        let dominant = comparison.dominant(1);
+        assert_eq!(
+            dominant,
+            vec![&Contribution {
+                left: 4,
+                right: 9,
+                multiplicity: 1,
+                value: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn test_compare_columns() {
+        let columns = vec![LEFT.to_vec(), RIGHT.to_vec(), LEFT.to_vec()];
+        let comparisons = compare_columns(&columns, Pairing::Sorted, Metric::L1).unwrap();
+        let totals = comparisons
+            .iter()
+            .map(|(columns, comparison)| (*columns, comparison.total))
+            .collect::<Vec<_>>();
+        assert_eq!(totals, vec![((0, 1), 11), ((0, 2), 0), ((1, 2), 11)]);
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let big = [u64::MAX as i128 * 4_000_000_000_000_000_000];
+        assert!(compare(&big, &[0], Pairing::Sorted, Metric::L2).is_err());
+
+        let costs = vec![vec![i128::MAX, 0], vec![0, i128::MAX]];
+        assert_eq!(optimal_assignment(&costs), Ok(vec![1, 0]));
+        let costs = vec![vec![i128::MIN, 0], vec![0, 0]];
+        assert!(optimal_assignment(&costs).is_err());
+    }
+}
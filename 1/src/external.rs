@@ -0,0 +1,402 @@
+//! Day 1 in bounded memory, for inputs too large to sort in memory.
+//!
+//! Each column is cut into runs of at most `run_size` values, which are sorted
+//! and spilled to temporary files. The runs are then k-way merged, at most
+//! `fan_in` files at a time, into one sorted file per column. Both parts are a
+//! single sequential pass over the two sorted columns.
+
+use aoc_2024_lib::rng::Rng;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Values kept in memory per column before spilling, 16 MiB worth of `i128`.
+pub const DEFAULT_RUN_SIZE: usize = 1 << 20;
+/// Number of runs merged at once, which bounds the open files.
+pub const DEFAULT_FAN_IN: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct ExternalSolver {
+    run_size: usize,
+    fan_in: usize,
+    dir: PathBuf,
+}
+
+impl Default for ExternalSolver {
+    fn default() -> Self {
+        Self {
+            run_size: DEFAULT_RUN_SIZE,
+            fan_in: DEFAULT_FAN_IN,
+            dir: std::env::temp_dir(),
+        }
+    }
+}
+
+impl ExternalSolver {
+    pub fn with_run_size(mut self, run_size: usize) -> Self {
+        self.run_size = run_size.max(1);
+        self
+    }
+
+    pub fn with_fan_in(mut self, fan_in: usize) -> Self {
+        self.fan_in = fan_in.max(2);
+        self
+    }
+
+    /// Directory for the spilled runs, the system temp directory by default.
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Reads `left right` lines and sorts both columns. Blank lines are skipped.
+    pub fn sort(&self, reader: impl BufRead) -> io::Result<SortedLists> {
+        let mut left = ColumnSorter::new(self);
+        let mut right = ColumnSorter::new(self);
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut values = line.split_whitespace();
+            let Some(l) = values.next() else {
+                continue;
+            };
+            let (Some(r), None) = (values.next(), values.next()) else {
+                return Err(invalid_data(format!(
+                    "Expected two numbers on line {}",
+                    index + 1
+                )));
+            };
+            left.push(parse_value(l, index)?)?;
+            right.push(parse_value(r, index)?)?;
+        }
+
+        Ok(SortedLists {
+            left: left.finish()?,
+            right: right.finish()?,
+        })
+    }
+}
+
+pub struct SortedLists {
+    left: SortedColumn,
+    right: SortedColumn,
+}
+
+impl SortedLists {
+    /// Part 1: sum of distances between the sorted columns.
+    pub fn distance(&self) -> io::Result<i128> {
+        let mut total = 0i128;
+        for (left, right) in self.left.values()?.zip(self.right.values()?) {
+            let distance = left?
+                .checked_sub(right?)
+                .and_then(i128::checked_abs)
+                .ok_or_else(|| invalid_data("Overflow in distance".to_string()))?;
+            total = checked_add(total, distance)?;
+        }
+        Ok(total)
+    }
+
+    /// Part 2: similarity score, as a merge join over both sorted columns.
+    pub fn similarity(&self) -> io::Result<i128> {
+        let mut left = self.left.values()?.peekable();
+        let mut right = self.right.values()?.peekable();
+        let mut total = 0i128;
+
+        let mut l = next_group(&mut left)?;
+        let mut r = next_group(&mut right)?;
+        while let (Some((left_value, left_count)), Some((right_value, right_count))) = (l, r) {
+            match left_value.cmp(&right_value) {
+                std::cmp::Ordering::Less => l = next_group(&mut left)?,
+                std::cmp::Ordering::Greater => r = next_group(&mut right)?,
+                std::cmp::Ordering::Equal => {
+                    let score = left_value
+                        .checked_mul(left_count)
+                        .and_then(|score| score.checked_mul(right_count))
+                        .ok_or_else(|| invalid_data("Overflow in similarity".to_string()))?;
+                    total = checked_add(total, score)?;
+                    l = next_group(&mut left)?;
+                    r = next_group(&mut right)?;
+                }
+            }
+        }
+        Ok(total)
+    }
+}
+
+/// Writes `lines` lines of AoC-like random location ids, reproducible by `seed`.
+pub fn write_generated(out: &mut impl Write, lines: u64, seed: u64) -> io::Result<()> {
+    let mut rng = Rng::new(seed);
+    let mut next = || 10_000 + rng.below(90_000);
+    for _ in 0..lines {
+        writeln!(out, "{}   {}", next(), next())?;
+    }
+    Ok(())
+}
+
+fn parse_value(value: &str, index: usize) -> io::Result<i128> {
+    value.parse().map_err(|e| {
+        invalid_data(format!(
+            "Invalid number {value:?} on line {}: {e}",
+            index + 1
+        ))
+    })
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn checked_add(total: i128, value: i128) -> io::Result<i128> {
+    total
+        .checked_add(value)
+        .ok_or_else(|| invalid_data("Overflow while summing".to_string()))
+}
+
+/// The next value and how many times it repeats.
+fn next_group<I>(values: &mut Peekable<I>) -> io::Result<Option<(i128, i128)>>
+where
+    I: Iterator<Item = io::Result<i128>>,
+{
+    let Some(value) = values.next().transpose()? else {
+        return Ok(None);
+    };
+    let mut count = 1;
+    loop {
+        match values.peek() {
+            Some(Ok(next)) if *next == value => {
+                values.next();
+                count += 1;
+            }
+            Some(Err(_)) => {
+                values.next().transpose()?;
+            }
+            _ => return Ok(Some((value, count))),
+        }
+    }
+}
+
+/// A sorted run on disk, removed when dropped.
+struct Run {
+    path: PathBuf,
+}
+
+impl Run {
+    fn create(dir: &Path, values: impl Iterator<Item = io::Result<i128>>) -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "aoc-2024-1-{}-{}.run",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let run = Run {
+            path: dir.join(name),
+        };
+
+        let mut writer = BufWriter::new(File::create(&run.path)?);
+        for value in values {
+            writer.write_all(&value?.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(run)
+    }
+
+    fn reader(&self) -> io::Result<RunReader> {
+        Ok(RunReader(BufReader::new(File::open(&self.path)?)))
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+struct RunReader(BufReader<File>);
+
+impl Iterator for RunReader {
+    type Item = io::Result<i128>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; 16];
+        match self.0.read_exact(&mut bytes) {
+            Ok(()) => Some(Ok(i128::from_le_bytes(bytes))),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// K-way merge of sorted runs.
+struct Merge {
+    readers: Vec<RunReader>,
+    heap: BinaryHeap<Reverse<(i128, usize)>>,
+}
+
+impl Merge {
+    fn new(runs: &[Run]) -> io::Result<Self> {
+        let mut readers = runs
+            .iter()
+            .map(Run::reader)
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut heap = BinaryHeap::with_capacity(readers.len());
+        for (index, reader) in readers.iter_mut().enumerate() {
+            if let Some(value) = reader.next().transpose()? {
+                heap.push(Reverse((value, index)));
+            }
+        }
+        Ok(Self { readers, heap })
+    }
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<i128>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((value, index)) = self.heap.pop()?;
+        match self.readers[index].next() {
+            Some(Ok(next)) => self.heap.push(Reverse((next, index))),
+            Some(Err(e)) => return Some(Err(e)),
+            None => {}
+        }
+        Some(Ok(value))
+    }
+}
+
+struct ColumnSorter<'a> {
+    solver: &'a ExternalSolver,
+    buffer: Vec<i128>,
+    runs: Vec<Run>,
+}
+
+impl<'a> ColumnSorter<'a> {
+    fn new(solver: &'a ExternalSolver) -> Self {
+        Self {
+            solver,
+            buffer: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, value: i128) -> io::Result<()> {
+        self.buffer.push(value);
+        if self.buffer.len() >= self.solver.run_size {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        self.buffer.sort_unstable();
+        let run = Run::create(&self.solver.dir, self.buffer.drain(..).map(Ok))?;
+        self.runs.push(run);
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<SortedColumn> {
+        if self.runs.is_empty() {
+            self.buffer.sort_unstable();
+            return Ok(SortedColumn::Memory(self.buffer));
+        }
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+
+        let mut runs = self.runs;
+        while runs.len() > 1 {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(self.solver.fan_in));
+            for group in runs.chunks(self.solver.fan_in) {
+                merged.push(Run::create(&self.solver.dir, Merge::new(group)?)?);
+            }
+            runs = merged;
+        }
+        Ok(SortedColumn::Disk(runs.pop().expect("at least one run")))
+    }
+}
+
+enum SortedColumn {
+    Memory(Vec<i128>),
+    Disk(Run),
+}
+
+impl SortedColumn {
+    fn values(&self) -> io::Result<Box<dyn Iterator<Item = io::Result<i128>> + '_>> {
+        Ok(match self {
+            SortedColumn::Memory(values) => Box::new(values.iter().copied().map(Ok)),
+            SortedColumn::Disk(run) => Box::new(run.reader()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::{compare, parse_columns, Metric, Pairing};
+
+    const SIMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-2024-1-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_example_in_memory() {
+        let sorted = ExternalSolver::default().sort(SIMPLE.as_bytes()).unwrap();
+        assert_eq!(sorted.distance().unwrap(), 11);
+        assert_eq!(sorted.similarity().unwrap(), 31);
+    }
+
+    #[test]
+    fn test_example_spilled() {
+        let dir = test_dir("example");
+        let solver = ExternalSolver::default()
+            .with_run_size(1)
+            .with_fan_in(2)
+            .with_dir(&dir);
+        let sorted = solver.sort(SIMPLE.as_bytes()).unwrap();
+        assert_eq!(sorted.distance().unwrap(), 11);
+        assert_eq!(sorted.similarity().unwrap(), 31);
+
+        drop(sorted);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_generated_matches_in_memory() {
+        let mut input = Vec::new();
+        write_generated(&mut input, 20_000, 7).unwrap();
+
+        let columns = parse_columns(std::str::from_utf8(&input).unwrap()).unwrap();
+        let expected = |metric| {
+            compare(&columns[0], &columns[1], Pairing::Sorted, metric)
+                .unwrap()
+                .total
+        };
+
+        let dir = test_dir("generated");
+        let solver = ExternalSolver::default()
+            .with_run_size(1000)
+            .with_fan_in(4)
+            .with_dir(&dir);
+        let sorted = solver.sort(input.as_slice()).unwrap();
+        assert_eq!(sorted.distance().unwrap(), expected(Metric::L1));
+        assert_eq!(sorted.similarity().unwrap(), expected(Metric::Similarity));
+
+        drop(sorted);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        let solver = ExternalSolver::default();
+        assert!(solver.sort("1 2\n3\n".as_bytes()).is_err());
+        assert!(solver.sort("1 2 3\n".as_bytes()).is_err());
+        assert!(solver.sort("1 x\n".as_bytes()).is_err());
+    }
+}
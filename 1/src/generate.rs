@@ -0,0 +1,20 @@
+//! Writes a generated day 1 input to stdout: `generate LINES [SEED]`.
+
+use aoc_2024_1::external::write_generated;
+use std::io::{self, BufWriter, Write};
+
+const USAGE: &str = "Usage: generate LINES [SEED]";
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let parse = |arg: Option<String>| arg.map(|arg| arg.parse::<u64>());
+    let (Some(Ok(lines)), Ok(seed)) = (parse(args.next()), parse(args.next()).unwrap_or(Ok(2024)))
+    else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    write_generated(&mut out, lines, seed)?;
+    out.flush()
+}
@@ -1,58 +1,61 @@
-fn ex_1_distances(input: &str) -> Result<u32, String> {
-    let mut left = Vec::new();
-    let mut right = Vec::new();
+use aoc_2024_1::compare::{compare_columns, parse_columns, Metric, Pairing};
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::tracing::debug;
+use std::io;
 
-    for line in input.lines() {
-        let mut nums = line.split_whitespace();
-        let Some(l) = nums.next() else {
-            continue;
-        };
-        let Some(r) = nums.next() else {
-            return Err(format!("No right number in {}", line));
-        };
-
-        left.push(l.parse::<u32>().unwrap());
-        right.push(r.parse::<u32>().unwrap());
+/// Compares every pair of columns and adds up the totals, which for the
+/// usual two columns is just the left list against the right one.
+fn compare_lists(input: &str, metric: Metric) -> Result<i128, String> {
+    let columns = parse_columns(input)?;
+    if columns.len() < 2 {
+        return Err("Expected at least two columns of location ids".to_string());
     }
 
-    left.sort();
-    right.sort();
+    let mut total: i128 = 0;
+    for ((i, j), comparison) in compare_columns(&columns, Pairing::Sorted, metric)? {
+        for contribution in comparison.dominant(5) {
+            debug!(i, j, ?metric, ?contribution, "dominant contribution");
+        }
+        total = comparison
+            .total
+            .checked_add(total)
+            .ok_or_else(|| "Overflow while adding up the column totals".to_string())?;
+    }
+    Ok(total)
+}
 
-    Ok(left
-        .iter()
-        .zip(right.iter())
-        .map(|(l, r)| l.abs_diff(*r))
-        .sum())
+fn ex_1_distances(input: &str) -> Result<i128, String> {
+    compare_lists(input, Metric::L1)
 }
 
-fn ex_2_similarity_score(input: &str) -> Result<u32, String> {
-    let mut counts = std::collections::HashMap::<u32, u32>::new();
+fn ex_2_similarity_score(input: &str) -> Result<i128, String> {
+    compare_lists(input, Metric::Similarity)
+}
 
-    let mut left = Vec::<u32>::new();
+fn invalid(name: &str, error: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{name}: {error}"))
+}
 
-    for line in input.lines() {
-        let mut nums = line.split_whitespace();
-        let Some(l) = nums.next().and_then(|s| s.parse::<u32>().ok()) else {
-            continue;
-        };
-        let Some(r) = nums.next().and_then(|s| s.parse::<u32>().ok()) else {
-            return Err(format!("No right number in {}", line));
-        };
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(1);
 
-        left.push(l);
-        *counts.entry(r).or_insert(0) += 1;
+    for input in args.load("./input.txt")? {
+        if args.runs_part(1) {
+            let record = reporter
+                .try_time(1, &input.name, || ex_1_distances(&input.content))
+                .map_err(|e| invalid(&input.name, e))?;
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+        if args.runs_part(2) {
+            let record = reporter
+                .try_time(2, &input.name, || ex_2_similarity_score(&input.content))
+                .map_err(|e| invalid(&input.name, e))?;
+            reporter.emit(&record, |r| r.answer.clone());
+        }
     }
 
-    Ok(left
-        .iter()
-        .map(|l| l * counts.get(l).unwrap_or(&0))
-        .sum::<u32>())
-}
-
-fn main() {
-    let input = std::fs::read_to_string("./input.txt").unwrap();
-    println!("{:?}", ex_1_distances(&input).unwrap());
-    println!("{:?}", ex_2_similarity_score(&input).unwrap());
+    Ok(())
 }
 
 #[cfg(test)]
@@ -77,4 +80,12 @@ mod tests {
     fn similarity_score_on_example() {
         assert_eq!(ex_2_similarity_score(SIMPLE), Ok(31));
     }
+
+    #[test]
+    fn overflowing_total_is_reported() {
+        // Each pair of columns is fine on its own, their sum is not.
+        let big = i128::MAX / 2 + 1;
+        let input = format!("0 {big} 0\n");
+        assert!(ex_1_distances(&input).is_err());
+    }
 }
@@ -0,0 +1,31 @@
+//! Day 1 in bounded memory, see [`aoc_2024_1::external`].
+//!
+//! `cargo run --release --bin stream -- --input big.txt`, where `big.txt` can
+//! come from `cargo run --release --bin generate -- 1000000000 > big.txt`.
+
+use aoc_2024_1::external::ExternalSolver;
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::tracing::info;
+use std::io;
+use std::time::Instant;
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(1);
+    let (name, reader) = args.open("./input.txt")?;
+
+    let start = Instant::now();
+    let sorted = ExternalSolver::default().sort(reader)?;
+    info!(elapsed = ?start.elapsed(), "sorted both columns");
+
+    if args.runs_part(1) {
+        let record = reporter.try_time(1, &name, || sorted.distance())?;
+        reporter.emit(&record, |r| r.answer.clone());
+    }
+    if args.runs_part(2) {
+        let record = reporter.try_time(2, &name, || sorted.similarity())?;
+        reporter.emit(&record, |r| r.answer.clone());
+    }
+
+    Ok(())
+}
@@ -6,6 +6,7 @@
 mod grid;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use aoc_2024_lib::cli::Args;
 use grid::Grid;
 
 type Height = u32;
@@ -138,10 +139,22 @@ mod ex2 {
     }
 }
 
-fn main() {
-    let input = std::fs::read_to_string("input.txt").unwrap();
-    println!("{}", ex1::solve(&input));
-    println!("{}", ex2::solve(&input));
+fn main() -> std::io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(10);
+
+    for input in args.load("input.txt")? {
+        if args.runs_part(1) {
+            let record = reporter.time(1, &input.name, || ex1::solve(&input.content));
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+        if args.runs_part(2) {
+            let record = reporter.time(2, &input.name, || ex2::solve(&input.content));
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
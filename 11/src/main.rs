@@ -1,4 +1,8 @@
-type Stone = u64;
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::num::{Num, Overflow};
+use std::io;
+
+type Stone = Num;
 
 fn parse_input(input: &str) -> Vec<Stone> {
     input
@@ -8,89 +12,113 @@ fn parse_input(input: &str) -> Vec<Stone> {
         .collect()
 }
 
-fn count_digits(stone: u64) -> u32 {
-    if stone == 0 {
-        return 1;
-    }
-    (stone as f64).log10() as u32 + 1
+fn count_digits(stone: &Stone) -> u32 {
+    stone.digits()
 }
 
-fn solve_ex1(stones: &[Stone], iterations: u32) -> usize {
+fn solve_ex1(stones: &[Stone], iterations: u32) -> Result<usize, Overflow> {
     let mut stones = stones.to_vec();
+    let year = Num::from(2024);
 
     for _ in 0..iterations {
         let mut new_stones = Vec::new();
 
         for stone in stones {
-            if stone == 0 {
-                new_stones.push(1);
+            if stone.is_zero() {
+                new_stones.push(Num::one());
                 continue;
             }
 
-            let digits = count_digits(stone);
-            if digits % 2 == 0 {
-                let mid = 10_u64.pow(digits / 2);
-                let left = stone / mid;
-                let right = stone % mid;
+            let digits = count_digits(&stone);
+            if digits.is_multiple_of(2) {
+                let mid = Num::pow10(digits / 2)?;
+                let left = stone.div(&mid)?;
+                let right = stone.rem(&mid)?;
                 new_stones.extend([left, right]);
                 continue;
             }
 
-            new_stones.push(stone * 2024);
+            new_stones.push(stone.mul(&year)?);
         }
 
         stones = new_stones;
     }
 
-    stones.len()
+    Ok(stones.len())
 }
 
 mod buckets {
     use super::*;
     use std::collections::HashMap;
 
-    pub fn solve(stones: &[Stone], iterations: u32) -> usize {
-        let mut buckets =
-            HashMap::<Stone, usize>::from_iter(stones.iter().map(|stone| (*stone, 1)));
+    fn add_count(
+        buckets: &mut HashMap<Stone, Num>,
+        stone: Stone,
+        count: &Num,
+    ) -> Result<(), Overflow> {
+        let entry = buckets.entry(stone).or_insert_with(Num::zero);
+        *entry = entry.add(count)?;
+        Ok(())
+    }
+
+    pub fn solve(stones: &[Stone], iterations: u32) -> Result<Num, Overflow> {
+        let mut buckets = HashMap::<Stone, Num>::from_iter(
+            stones.iter().map(|stone| (stone.clone(), Num::one())),
+        );
+        let year = Num::from(2024);
 
         for _ in 0..iterations {
             let mut new_buckets = HashMap::new();
 
             for (stone, count) in buckets.iter() {
-                if *stone == 0 {
-                    *new_buckets.entry(1).or_insert(0) += count;
+                if stone.is_zero() {
+                    add_count(&mut new_buckets, Num::one(), count)?;
                     continue;
                 }
 
-                let digits = count_digits(*stone);
-                if digits % 2 == 0 {
-                    let mid = 10_u64.pow(digits / 2);
-                    *new_buckets.entry(stone / mid).or_insert(0) += count;
-                    *new_buckets.entry(stone % mid).or_insert(0) += count;
+                let digits = count_digits(stone);
+                if digits.is_multiple_of(2) {
+                    let mid = Num::pow10(digits / 2)?;
+                    add_count(&mut new_buckets, stone.div(&mid)?, count)?;
+                    add_count(&mut new_buckets, stone.rem(&mid)?, count)?;
                     continue;
                 }
 
-                *new_buckets.entry(stone * 2024).or_insert(0) += *count;
+                add_count(&mut new_buckets, stone.mul(&year)?, count)?;
             }
 
             buckets = new_buckets;
         }
 
-        buckets.values().sum()
+        Num::try_sum(buckets.into_values())
     }
 }
 
-fn main() {
-    let input = std::fs::read_to_string("input.txt").unwrap();
-    let stones = parse_input(&input);
-    let result = solve_ex1(&stones, 25);
-    println!("{:?}", result);
-
-    // measure time
-    let start = std::time::Instant::now();
-    let result = buckets::solve(&stones, 75);
-    println!("{:?}", result);
-    println!("Time: {:?}", start.elapsed());
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(11);
+
+    for input in args.load("input.txt")? {
+        let stones = parse_input(&input.content);
+
+        if args.runs_part(1) {
+            let record = reporter
+                .try_time(1, &input.name, || solve_ex1(&stones, 25))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+
+        if args.runs_part(2) {
+            let record = reporter
+                .try_time(2, &input.name, || buckets::solve(&stones, 75))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            reporter.emit(&record, |r| {
+                format!("{}\nTime: {:?}", r.answer, r.duration())
+            });
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -103,7 +131,10 @@ mod tests {
             125, 17, 1000, 9999, 99999, 999999, 9999999, 99999999, 999999999,
         ];
         for number in numbers {
-            assert_eq!(count_digits(number), number.to_string().len() as u32);
+            assert_eq!(
+                count_digits(&Num::from(number)),
+                number.to_string().len() as u32
+            );
         }
     }
 
@@ -114,7 +145,7 @@ mod tests {
         let stones = parse_input(EXAMPLE);
         let result = solve_ex1(&stones, 25);
         println!("{:?}", result);
-        assert_eq!(result, 55312);
+        assert_eq!(result, Ok(55312));
     }
 
     #[test]
@@ -122,6 +153,15 @@ mod tests {
         let stones = parse_input(EXAMPLE);
         let result = buckets::solve(&stones, 25);
         println!("{:?}", result);
-        assert_eq!(result, 55312);
+        assert_eq!(result, Ok(Num::from(55312)));
+    }
+
+    #[cfg(feature = "checked")]
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_overflow_is_reported() {
+        // 39 digits, so the first blink multiplies it by 2024.
+        let stones = parse_input("100000000000000000000000000000000000000");
+        assert!(buckets::solve(&stones, 1).is_err());
     }
 }
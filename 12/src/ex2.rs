@@ -31,7 +31,7 @@ fn corner_in_window(window: &[isize; 4], region_symbol: isize) -> u8 {
 
     0
 }
-    
+
 #[derive(Debug, PartialEq, Eq)]
 struct Region {
     symbol: char,
@@ -141,7 +141,7 @@ pub fn solve(input: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::input_reader;
+    use aoc_2024_lib::input_reader;
 
     #[test]
     fn test_single_line() -> Result<(), Box<dyn std::error::Error>> {
@@ -1,20 +1,51 @@
 mod ex1;
 mod ex2;
 mod grid;
-mod input_reader;
+
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::report::{Format, Record};
 
 fn main() -> Result<(), std::io::Error> {
-    let inputs = input_reader::read_input("inputs.md")?;
+    let args = Args::from_env();
+    let reporter = args.reporter(12);
+
+    reporter.text(|| {
+        format!(
+            "\n{:<10}\tPrice   \tDiscount\n{:<10}\t--------\t--------",
+            "Input", "--------"
+        )
+    });
+    for input in args.load("inputs.md")? {
+        let price = args
+            .runs_part(1)
+            .then(|| reporter.time(1, &input.name, || ex1::solve(&input.content)));
+        let price_with_bulk_discount = args
+            .runs_part(2)
+            .then(|| reporter.time(2, &input.name, || ex2::solve(&input.content)));
 
-    println!("\n{:<10}\tPrice   \tDiscount", "Input");
-    println!("{:<10}\t--------\t--------", "--------");
-    for input in inputs.sections {
-        let price = ex1::solve(&input.content);
-        let price_with_bulk_discount = ex2::solve(&input.content);
-        println!(
-            "{:<10}\t{:<10}\t{}",
-            input.name, price, price_with_bulk_discount
-        );
+        match reporter.format() {
+            Format::Text => {
+                let answer = |record: &Option<Record>| {
+                    record
+                        .as_ref()
+                        .map_or("-".to_string(), |r| r.answer.clone())
+                };
+                println!(
+                    "{:<10}\t{:<10}\t{}",
+                    input.name,
+                    answer(&price),
+                    answer(&price_with_bulk_discount)
+                )
+            }
+            Format::Json => {
+                for record in price.iter().chain(price_with_bulk_discount.iter()) {
+                    println!("{}", record.to_json());
+                }
+            }
+        }
+        for record in price.iter().chain(price_with_bulk_discount.iter()) {
+            reporter.check(record);
+        }
     }
 
     Ok(())
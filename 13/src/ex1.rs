@@ -1,44 +1,62 @@
 use crate::parse_input::{Game, A_PRESS_COST, B_PRESS_COST};
+use aoc_2024_lib::num::{Num, Overflow};
 
-pub fn solve_round(round: &crate::parse_input::GameRound) -> Option<i64> {
-    let ax = round.button_a.x;
-    let ay = round.button_a.y;
-    let bx = round.button_b.x;
-    let by = round.button_b.y;
-    let px = round.prize.x;
-    let py = round.prize.y;
+pub fn solve_round(round: &crate::parse_input::GameRound) -> Result<Option<Num>, Overflow> {
+    let ax = &round.button_a.x;
+    let ay = &round.button_a.y;
+    let bx = &round.button_b.x;
+    let by = &round.button_b.y;
+    let px = &round.prize.x;
+    let py = &round.prize.y;
 
     // Using Cramer's rule:
     // D = | ax  bx |
     //     | ay  by |
-    let d = ax * by - ay * bx;
-    if d == 0 {
+    let d = ax.mul(by)?.sub(&ay.mul(bx)?)?;
+    if d.is_zero() {
         // Buttons move in same direction
-        return None;
+        return Ok(None);
     }
 
     // Da = | px  bx |
     //      | py  by |
-    let da = px * by - py * bx;
+    let da = px.mul(by)?.sub(&py.mul(bx)?)?;
 
     // Db = | ax  px |
     //      | ay  py |
-    let db = ax * py - ay * px;
+    let db = ax.mul(py)?.sub(&ay.mul(px)?)?;
 
-    let a = da / d;
-    let b = db / d;
+    let a = da.div(&d)?;
+    let b = db.div(&d)?;
 
     // Check if we have a valid solution
-    if a >= 0 && b >= 0 && a * ax + b * bx == px && a * ay + b * by == py {
-        Some(a * A_PRESS_COST + b * B_PRESS_COST)
+    if !a.is_negative()
+        && !b.is_negative()
+        && a.mul(ax)?.add(&b.mul(bx)?)? == *px
+        && a.mul(ay)?.add(&b.mul(by)?)? == *py
+    {
+        let cost = a
+            .mul(&Num::from(A_PRESS_COST))?
+            .add(&b.mul(&Num::from(B_PRESS_COST))?)?;
+        Ok(Some(cost))
     } else {
-        None
+        Ok(None)
     }
 }
 
-pub fn solve(input: &str) -> i64 {
+pub fn solve(input: &str) -> Result<Num, Overflow> {
     let game = input.parse::<Game>().expect("valid game input");
-    game.rounds.iter().filter_map(solve_round).sum()
+    solve_game(&game)
+}
+
+pub fn solve_game(game: &Game) -> Result<Num, Overflow> {
+    let mut total = Num::zero();
+    for round in game.rounds.iter() {
+        if let Some(cost) = solve_round(round)? {
+            total = total.add(&cost)?;
+        }
+    }
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -55,7 +73,10 @@ Button A: X+94, Y+34
 Button B: X+22, Y+67
 Prize: X=8400, Y=5400";
 
-        assert_eq!(solve_round(&input.parse::<GameRound>()?), Some(280));
+        assert_eq!(
+            solve_round(&input.parse::<GameRound>()?),
+            Ok(Some(Num::from(280)))
+        );
         Ok(())
     }
 
@@ -66,14 +87,17 @@ Button A: X+20, Y+20
 Button B: X+50, Y+50
 Prize: X=10, Y=10";
 
-        assert_eq!(solve_round(&input.parse::<GameRound>()?), None);
+        assert_eq!(solve_round(&input.parse::<GameRound>()?), Ok(None));
         Ok(())
     }
 
     #[test]
     fn test_full_example() -> Result<(), Box<dyn std::error::Error>> {
         let inputs = read_input("./inputs.md")?;
-        assert_eq!(solve(&inputs.get_input("Example").content), 480);
+        assert_eq!(
+            solve(&inputs.get_input("Example").content),
+            Ok(Num::from(480))
+        );
         Ok(())
     }
 }
@@ -1,21 +1,23 @@
 use crate::{ex1, parse_input::Game};
+use aoc_2024_lib::num::{Num, Overflow};
 
 static PRIZE_SHIFT: i64 = 10000000000000;
 
-fn shift_prizes(game: &Game) -> Game {
+fn shift_prizes(game: &Game) -> Result<Game, Overflow> {
+    let shift = Num::from(PRIZE_SHIFT);
     let mut game = game.clone();
     for round in game.rounds.iter_mut() {
-        round.prize.x += PRIZE_SHIFT;
-        round.prize.y += PRIZE_SHIFT;
+        round.prize.x = round.prize.x.add(&shift)?;
+        round.prize.y = round.prize.y.add(&shift)?;
     }
-    game
+    Ok(game)
 }
 
-pub fn solve(input: &str) -> i64 {
+pub fn solve(input: &str) -> Result<Num, Overflow> {
     let game = input.parse::<Game>().expect("valid game input");
-    let game = shift_prizes(&game);
+    let game = shift_prizes(&game)?;
 
-    game.rounds.iter().filter_map(ex1::solve_round).sum()
+    ex1::solve_game(&game)
 }
 
 #[cfg(test)]
@@ -23,7 +25,7 @@ mod tests {
     use ex1::solve_round;
 
     use super::*;
-    use crate::input_reader::read_input;
+    use aoc_2024_lib::input_reader::read_input;
 
     #[test]
     fn test_full_example() -> Result<(), Box<dyn std::error::Error>> {
@@ -32,14 +34,20 @@ mod tests {
 
         // "now it is only possible to win on 2nd and 4th claw machines"
         let game = example.parse::<Game>()?;
-        let game = shift_prizes(&game);
-
-        assert_eq!(solve_round(&game.rounds[0]), None);
-        assert_eq!(solve_round(&game.rounds[1]), Some(459236326669));
-        assert_eq!(solve_round(&game.rounds[2]), None);
-        assert_eq!(solve_round(&game.rounds[3]), Some(416082282239));
-
-        assert_eq!(solve(example), 875318608908);
+        let game = shift_prizes(&game)?;
+
+        assert_eq!(solve_round(&game.rounds[0]), Ok(None));
+        assert_eq!(
+            solve_round(&game.rounds[1]),
+            Ok(Some(Num::from(459236326669)))
+        );
+        assert_eq!(solve_round(&game.rounds[2]), Ok(None));
+        assert_eq!(
+            solve_round(&game.rounds[3]),
+            Ok(Some(Num::from(416082282239)))
+        );
+
+        assert_eq!(solve(example), Ok(Num::from(875318608908)));
         Ok(())
     }
 }
@@ -2,15 +2,27 @@ mod ex1;
 mod ex2;
 mod parse_input;
 
-use aoc_2024_lib::input_reader;
+use aoc_2024_lib::cli::Args;
+use std::io;
 
-fn main() -> Result<(), std::io::Error> {
-    let inputs = input_reader::read_input("inputs.md")?;
+fn main() -> Result<(), io::Error> {
+    let args = Args::from_env();
+    let reporter = args.reporter(13);
 
-    for input in inputs.sections {
-        println!("\n{}", input.name);
-        println!("{}", ex1::solve(&input.content));
-        println!("{}", ex2::solve(&input.content));
+    for input in args.load("inputs.md")? {
+        reporter.text(|| format!("\n{}", input.name));
+        if args.runs_part(1) {
+            let record = reporter
+                .try_time(1, &input.name, || ex1::solve(&input.content))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+        if args.runs_part(2) {
+            let record = reporter
+                .try_time(2, &input.name, || ex2::solve(&input.content))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            reporter.emit(&record, |r| r.answer.clone());
+        }
     }
 
     Ok(())
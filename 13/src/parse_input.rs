@@ -1,15 +1,17 @@
+use aoc_2024_lib::num::Num;
+
 #[derive(Debug, parse_display::Display, parse_display::FromStr, Clone)]
 #[display("X{x}, Y{y}")]
 pub struct ButtonPress {
-    pub x: i64,
-    pub y: i64,
+    pub x: Num,
+    pub y: Num,
 }
 
 #[derive(Debug, parse_display::Display, parse_display::FromStr, Clone)]
 #[display("X={x}, Y={y}")]
 pub struct Prize {
-    pub x: i64,
-    pub y: i64,
+    pub x: Num,
+    pub y: Num,
 }
 
 #[derive(Debug, parse_display::Display, parse_display::FromStr, Clone)]
@@ -60,20 +62,20 @@ Prize: X=12748, Y=12176";
         assert_eq!(game.rounds.len(), 2);
 
         let first = &game.rounds[0];
-        assert_eq!(first.button_a.x, 94);
-        assert_eq!(first.button_a.y, 34);
-        assert_eq!(first.button_b.x, 22);
-        assert_eq!(first.button_b.y, 67);
-        assert_eq!(first.prize.x, 8400);
-        assert_eq!(first.prize.y, 5400);
+        assert_eq!(first.button_a.x, Num::from(94));
+        assert_eq!(first.button_a.y, Num::from(34));
+        assert_eq!(first.button_b.x, Num::from(22));
+        assert_eq!(first.button_b.y, Num::from(67));
+        assert_eq!(first.prize.x, Num::from(8400));
+        assert_eq!(first.prize.y, Num::from(5400));
 
         let second = &game.rounds[1];
-        assert_eq!(second.button_a.x, 26);
-        assert_eq!(second.button_a.y, 66);
-        assert_eq!(second.button_b.x, 67);
-        assert_eq!(second.button_b.y, 21);
-        assert_eq!(second.prize.x, 12748);
-        assert_eq!(second.prize.y, 12176);
+        assert_eq!(second.button_a.x, Num::from(26));
+        assert_eq!(second.button_a.y, Num::from(66));
+        assert_eq!(second.button_b.x, Num::from(67));
+        assert_eq!(second.button_b.y, Num::from(21));
+        assert_eq!(second.prize.x, Num::from(12748));
+        assert_eq!(second.prize.y, Num::from(12176));
 
         Ok(())
     }
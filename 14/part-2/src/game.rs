@@ -1,3 +1,5 @@
+use aoc_2024_lib::trace;
+
 pub const WIDTH: usize = 101;
 pub const HEIGHT: usize = 103;
 pub const UNIQUE_BOARDS: usize = WIDTH * HEIGHT;
@@ -48,8 +50,7 @@ impl Game {
         // Precompute all states
         for time in 0..UNIQUE_BOARDS {
             if time % 1000 == 0 {
-                print!("\x1B[2J\x1B[1;1H");
-                println!("Processing time: {}", time);
+                trace::progress(time, UNIQUE_BOARDS);
             }
 
             let board = process_time(&game.robots, time);
@@ -65,9 +66,6 @@ impl Game {
             }
         }
 
-        // Clear the progress messages before starting UI
-        print!("\x1B[2J\x1B[1;1H");
-
         game
     }
 
@@ -6,6 +6,7 @@ use std::{fs, io};
 use ui::Ui;
 
 fn main() -> io::Result<()> {
+    aoc_2024_lib::trace::init();
     let input = fs::read_to_string("../input.txt").expect("Failed to read input file");
     let game = Game::new(&input);
     let mut ui = Ui::new()?;
@@ -1,5 +1,5 @@
 use crate::{ex1::cell_in_direction, parse_input::*};
-use aoc_2024_lib::{board::Board, point2::Point2};
+use aoc_2024_lib::{board::Board, point2::Point2, tracing::trace};
 use parse_display::{Display, FromStr};
 
 #[derive(Debug, Display, FromStr, PartialEq, Eq, Clone, Copy)]
@@ -49,10 +49,7 @@ fn move_robot(map: &Board<UpscaledToken>, moves: &[Direction]) -> Board<Upscaled
             robot_pos = new_pos;
         }
 
-        if cfg!(debug_assertions) {
-            println!("{}", direction);
-            println!("{}", map);
-        }
+        trace!(%direction, "robot moved\n{map}");
 
         debug_assert!(check_box_integrity(&map));
     }
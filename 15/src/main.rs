@@ -1,4 +1,4 @@
-use aoc_2024_lib::input_reader::read_input;
+use aoc_2024_lib::cli::Args;
 use parse_input::parse_input;
 
 mod ex1;
@@ -6,15 +6,22 @@ mod ex2;
 mod parse_input;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let input_file = read_input("./inputs.md")?;
+    let args = Args::from_env();
+    let reporter = args.reporter(15);
 
-    println!("\nDay 15");
+    reporter.text(|| "\nDay 15".to_string());
 
-    for input in input_file.sections {
-        let ex1_result = ex1::solve(&parse_input(&input.content)?);
-        let ex2_result = ex2::solve(&parse_input(&input.content)?);
-        println!("ex1 {}:\t{}", input.name, ex1_result);
-        println!("ex2 {}:\t{}", input.name, ex2_result);
+    for input in args.load("./inputs.md")? {
+        if args.runs_part(1) {
+            let parsed = parse_input(&input.content)?;
+            let result = reporter.time(1, &input.name, || ex1::solve(&parsed));
+            reporter.emit(&result, |r| format!("ex1 {}:\t{}", r.input, r.answer));
+        }
+        if args.runs_part(2) {
+            let parsed = parse_input(&input.content)?;
+            let result = reporter.time(2, &input.name, || ex2::solve(&parsed));
+            reporter.emit(&result, |r| format!("ex2 {}:\t{}", r.input, r.answer));
+        }
     }
 
     Ok(())
@@ -3,16 +3,23 @@ mod ex2;
 
 use std::error::Error;
 
-use aoc_2024_lib::input_reader::read_input;
+use aoc_2024_lib::cli::Args;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let inputs = read_input("./inputs.md")?;
+    let args = Args::from_env();
+    let reporter = args.reporter(16);
 
-    for input in inputs.sections {
-        let result = ex1::solve(&input.content);
-        let result2 = ex2::sum_all_points_on_shortest_paths(&input.content);
-        println!("ex1 {}: {}", input.name, result);
-        println!("ex2 {}: {}", input.name, result2);
+    for input in args.load("./inputs.md")? {
+        if args.runs_part(1) {
+            let result = reporter.time(1, &input.name, || ex1::solve(&input.content));
+            reporter.emit(&result, |r| format!("ex1 {}: {}", r.input, r.answer));
+        }
+        if args.runs_part(2) {
+            let result = reporter.time(2, &input.name, || {
+                ex2::sum_all_points_on_shortest_paths(&input.content)
+            });
+            reporter.emit(&result, |r| format!("ex2 {}: {}", r.input, r.answer));
+        }
     }
     Ok(())
 }
@@ -1,15 +1,18 @@
 mod ex1;
 mod types;
 
-use aoc_2024_lib::input_reader::read_input;
+use aoc_2024_lib::cli::Args;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let inputs = read_input("./inputs.md")?;
+    let args = Args::from_env();
+    let reporter = args.reporter(17);
 
-    for input in inputs.sections {
-        let result1 = ex1::solve(&input.content);
-        println!("{}\t{}", input.name, result1);
+    for input in args.load("./inputs.md")? {
+        if args.runs_part(1) {
+            let result1 = reporter.time(1, &input.name, || ex1::solve(&input.content));
+            reporter.emit(&result1, |r| format!("{}\t{}", r.input, r.answer));
+        }
     }
 
     Ok(())
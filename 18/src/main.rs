@@ -1,20 +1,34 @@
 mod ex1;
 mod ex2;
 
-use aoc_2024_lib::input_reader::read_input;
+use aoc_2024_lib::cli::Args;
 use std::error::Error;
+use std::time::Instant;
 
 static SIZE: u8 = 70;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let inputs = read_input("./inputs.md")?;
+    let args = Args::from_env();
+    let reporter = args.reporter(18);
 
-    let input = inputs.get_input("Input");
-    let result1 = ex1::solve(&input.content, SIZE, 1024);
-    println!("ex1\t{}", result1);
+    for input in args.load_section("./inputs.md", "Input")? {
+        if args.runs_part(1) {
+            let result1 = reporter.time(1, &input.name, || ex1::solve(&input.content, SIZE, 1024));
+            reporter.emit(&result1, |r| format!("ex1\t{}", r.answer));
+        }
 
-    let result2 = ex2::solve(&input.content, SIZE);
-    println!("ex2\t{:?}", result2);
+        if args.runs_part(2) {
+            let start = Instant::now();
+            let result2 = ex2::solve(&input.content, SIZE);
+            let record = reporter.record(
+                2,
+                &input.name,
+                format!("{},{}", result2.0, result2.1),
+                start.elapsed(),
+            );
+            reporter.emit(&record, |_| format!("ex2\t{:?}", result2));
+        }
+    }
 
     Ok(())
 }
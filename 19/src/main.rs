@@ -1,5 +1,7 @@
 use anyhow::Result;
-use aoc_2024_lib::input_reader::{self, Input};
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::input_reader::Input;
+use aoc_2024_lib::report::Format;
 
 mod ex1;
 mod ex2;
@@ -7,12 +9,30 @@ mod parser;
 mod trie;
 
 fn main() -> Result<()> {
-    let inputs = input_reader::read_input("./inputs.md")?;
+    let args = Args::from_env();
+    let reporter = args.reporter(19);
 
-    for Input { name, content } in inputs.sections {
-        let result1 = ex1::solve(&content)?;
-        let result2 = ex2::solve(&content)?;
-        println!("ex1\t{name}\t{result1}\t{result2}");
+    for Input { name, content } in args.load("./inputs.md")? {
+        let result1 = args
+            .runs_part(1)
+            .then(|| reporter.try_time(1, &name, || ex1::solve(&content)))
+            .transpose()?;
+        let result2 = args
+            .runs_part(2)
+            .then(|| reporter.try_time(2, &name, || ex2::solve(&content)))
+            .transpose()?;
+        let results = result1.iter().chain(&result2);
+        match reporter.format() {
+            Format::Text => {
+                let answers = results
+                    .clone()
+                    .map(|r| r.answer.as_str())
+                    .collect::<Vec<_>>();
+                println!("ex1\t{name}\t{}", answers.join("\t"))
+            }
+            Format::Json => results.clone().for_each(|r| println!("{}", r.to_json())),
+        }
+        results.for_each(|r| reporter.check(r));
     }
 
     Ok(())
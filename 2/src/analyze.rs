@@ -1,8 +1,15 @@
 use aoc_2024_2::{ex_2, ex_2_brute, parse_input};
-use std::fs;
+use aoc_2024_lib::cli::Args;
 
 fn main() {
-    let input = fs::read_to_string("./input.txt").unwrap();
+    let args = Args::from_env();
+    let input = args
+        .load("./input.txt")
+        .unwrap()
+        .into_iter()
+        .map(|input| input.content)
+        .collect::<Vec<_>>()
+        .join("\n");
     let parsed_input = parse_input(&input);
 
     // Compare results for each report
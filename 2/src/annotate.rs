@@ -0,0 +1,103 @@
+//! Text rendering of a [`Verdict`], used by the `explain` binary.
+
+use crate::rules::Verdict;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Colours for terminals: the bad level in bold red, removals struck through.
+    Ansi,
+    /// Markers for everything else: `[bad]` and `~removed~`.
+    Plain,
+}
+
+/// The report's levels with the level breaking a rule and the levels to
+/// remove marked.
+pub fn annotate(report: &[i32], verdict: &Verdict, style: Style) -> String {
+    let bad = verdict.violation.map(|violation| violation.index);
+    let removals = verdict.removals.as_deref().unwrap_or_default();
+
+    report
+        .iter()
+        .enumerate()
+        .map(|(index, level)| {
+            let mut level = level.to_string();
+            if bad == Some(index) {
+                level = match style {
+                    Style::Ansi => format!("\x1b[1;31m{level}\x1b[0m"),
+                    Style::Plain => format!("[{level}]"),
+                };
+            }
+            if removals.contains(&index) {
+                level = match style {
+                    Style::Ansi => format!("\x1b[9;33m{level}\x1b[0m"),
+                    Style::Plain => format!("~{level}~"),
+                };
+            }
+            level
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// One line per report: verdict, annotated levels and the reason.
+pub fn describe(report: &[i32], verdict: &Verdict, style: Style) -> String {
+    let status = if verdict.safe { "safe" } else { "unsafe" };
+    let annotated = annotate(report, verdict, style);
+
+    let Some(violation) = verdict.violation else {
+        return format!("{status:<6}  {annotated}");
+    };
+    let fix = match verdict.removals.as_deref() {
+        Some(removals) => format!(
+            "remove {} level(s) at {}",
+            removals.len(),
+            removals
+                .iter()
+                .map(|index| index.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => "cannot be fixed by removing levels".to_string(),
+    };
+    format!(
+        "{status:<6}  {annotated}  level {}: {}; {fix}",
+        violation.index, violation.kind
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Safety;
+
+    #[test]
+    fn test_annotate_plain() {
+        let report = [9, 7, 6, 2, 1];
+        let verdict = Safety::part_2().explain(&report);
+        assert_eq!(annotate(&report, &verdict, Style::Plain), "9 7 6 ~[2]~ ~1~");
+        assert_eq!(
+            describe(&report, &verdict, Style::Plain),
+            "unsafe  9 7 6 ~[2]~ ~1~  level 3: delta too large, changed by -4 but at most 3 allowed; remove 2 level(s) at 3, 4"
+        );
+    }
+
+    #[test]
+    fn test_describe_safe() {
+        let report = [7, 6, 4, 2, 1];
+        let verdict = Safety::part_2().explain(&report);
+        assert_eq!(
+            describe(&report, &verdict, Style::Plain),
+            "safe    7 6 4 2 1"
+        );
+    }
+
+    #[test]
+    fn test_annotate_ansi() {
+        let report = [1, 3, 2];
+        let verdict = Safety::part_1().explain(&report);
+        assert_eq!(
+            annotate(&report, &verdict, Style::Ansi),
+            "\x1b[9;33m1\x1b[0m 3 \x1b[1;31m2\x1b[0m"
+        );
+    }
+}
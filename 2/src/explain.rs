@@ -0,0 +1,36 @@
+//! Prints every report with the level that breaks a rule highlighted and the
+//! levels to remove to make it safe: `cargo run --bin explain -- --part 1`.
+
+use aoc_2024_2::annotate::{describe, Style};
+use aoc_2024_2::parse_input_numbered;
+use aoc_2024_2::rules::Safety;
+use aoc_2024_lib::cli::Args;
+use std::io::{self, IsTerminal};
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let safety = match args.part {
+        Some(1) => Safety::part_1(),
+        _ => Safety::part_2(),
+    };
+    let style = if io::stdout().is_terminal() {
+        Style::Ansi
+    } else {
+        Style::Plain
+    };
+
+    for input in args.load("./input.txt")? {
+        let reports = parse_input_numbered(&input.content).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {e}", input.name))
+        })?;
+        let mut safe = 0;
+        for (line, report) in &reports {
+            let verdict = safety.explain(report);
+            safe += usize::from(verdict.safe);
+            println!("{line:>4}  {}", describe(report, &verdict, style));
+        }
+        println!("{}: {safe} of {} reports safe", input.name, reports.len());
+    }
+
+    Ok(())
+}
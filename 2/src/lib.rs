@@ -1,19 +1,106 @@
+use std::fmt;
+use std::num::ParseIntError;
+
 pub type Report = Vec<i32>;
 pub type ParsedInput = Vec<Report>;
 
+/// Lenient parsing: tokens that are not levels are silently dropped.
 pub fn parse_input(input: &str) -> ParsedInput {
     input
         .trim()
         .lines()
+        .filter(|line| !is_comment_line(line))
         .map(|line| {
-            line.split_whitespace()
-                .take_while(|&token| token != "#")
-                .filter_map(|token| token.parse::<i32>().ok())
+            tokens(line)
+                .into_iter()
+                .filter_map(|(_, token)| token.parse::<i32>().ok())
                 .collect::<Report>()
         })
         .collect::<ParsedInput>()
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based position of the token in the input.
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub source: ParseIntError,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: invalid level {:?} ({})",
+            self.line, self.column, self.token, self.source
+        )
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Strict parsing: every token has to be a level. Blank lines and comments
+/// are skipped.
+pub fn parse_input_strict(input: &str) -> Result<ParsedInput, ParseError> {
+    parse_input_numbered(input)
+        .map(|reports| reports.into_iter().map(|(_, report)| report).collect())
+}
+
+/// [`parse_input_strict`], keeping the 1-based line of each report.
+pub fn parse_input_numbered(input: &str) -> Result<Vec<(usize, Report)>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !is_comment_line(line))
+        .map(|(index, line)| {
+            let report = tokens(line)
+                .into_iter()
+                .map(|(column, token)| {
+                    token.parse::<i32>().map_err(|source| ParseError {
+                        line: index + 1,
+                        column,
+                        token: token.to_string(),
+                        source,
+                    })
+                })
+                .collect::<Result<Report, _>>()?;
+            Ok((index + 1, report))
+        })
+        .collect()
+}
+
+fn is_comment_line(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+/// Whitespace separated tokens before any `#` comment, with their 1-based
+/// column.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let code = &line[..line.find('#').unwrap_or(line.len())];
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (index, c) in code.char_indices().chain([(code.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(index),
+            (true, Some(token_start)) => {
+                let column = code[..token_start].chars().count() + 1;
+                tokens.push((column, &code[token_start..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+pub mod annotate;
 pub mod ex_1;
 pub mod ex_2;
-pub mod ex_2_brute; 
+pub mod ex_2_brute;
+pub mod rules;
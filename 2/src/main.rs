@@ -1,19 +1,43 @@
-use aoc_2024_2::{ex_1, ex_2, ex_2_brute, parse_input};
-
-fn main() {
-    let input = std::fs::read_to_string("./input.txt").unwrap();
-    let parsed_input = parse_input(&input);
-    println!("{:?}", ex_1::count_safe_reports(&parsed_input));
-    println!(
-        "{:?}",
-        ex_2::count_safe_reports_with_problem_dampener(&parsed_input)
-    );
-    println!("{:?}", ex_2_brute::count_safe_reports(&parsed_input));
+use aoc_2024_2::{ex_1, ex_2, ex_2_brute, parse_input_strict};
+use aoc_2024_lib::cli::Args;
+use std::io;
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(2);
+
+    for input in args.load("./input.txt")? {
+        let parsed_input = parse_input_strict(&input.content).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {e}", input.name))
+        })?;
+
+        if args.runs_part(1) {
+            let record = reporter.time(1, &input.name, || ex_1::count_safe_reports(&parsed_input));
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+
+        if args.runs_part(2) {
+            let record = reporter.time(2, &input.name, || {
+                ex_2::count_safe_reports_with_problem_dampener(&parsed_input)
+            });
+            reporter.emit(&record, |r| r.answer.clone());
+
+            let record = reporter
+                .time(2, &input.name, || {
+                    ex_2_brute::count_safe_reports(&parsed_input)
+                })
+                .with_diagnostic("brute force");
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2024_2::{parse_input, parse_input_numbered};
 
     const SIMPLE: &str = r#"
             7 6 4 2 1
@@ -47,6 +71,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ignores_comments_without_space() {
+        assert_eq!(
+            parse_input("# full line comment\n1 2 3#note\n  # indented\n4 5"),
+            vec![vec![1, 2, 3], vec![4, 5]]
+        );
+    }
+
+    #[test]
+    fn lenient_parsing_drops_invalid_tokens() {
+        assert_eq!(parse_input("7 6 x 2"), vec![vec![7, 6, 2]]);
+    }
+
+    #[test]
+    fn strict_parsing_reports_position() {
+        let error = parse_input_strict("7 6 4 2 1\n\n  7 6 x 2").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.token, "x");
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 7: invalid level \"x\" (invalid digit found in string)"
+        );
+    }
+
+    #[test]
+    fn strict_parsing_accepts_comments() {
+        assert_eq!(
+            parse_input_strict("# header\n1 2 3 # 4\n\n5 6#7\n"),
+            Ok(vec![vec![1, 2, 3], vec![5, 6]])
+        );
+        assert_eq!(parse_input_strict(SIMPLE), Ok(parse_input(SIMPLE)));
+        assert_eq!(
+            parse_input_numbered("# header\n1 2 3 # 4\n\n5 6#7\n"),
+            Ok(vec![(2, vec![1, 2, 3]), (4, vec![5, 6])])
+        );
+    }
+
     #[test]
     fn ex_1_example() {
         assert_eq!(ex_1::count_safe_reports(&parse_input(SIMPLE)), 2);
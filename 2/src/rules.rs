@@ -0,0 +1,548 @@
+//! Configurable report safety.
+//!
+//! A [`Safety`] is a set of [`Rule`]s that must all hold between consecutive
+//! levels, plus a problem dampener that may remove up to `k` levels. The
+//! puzzle's rules are [`Safety::part_1`] and [`Safety::part_2`].
+//!
+//! The fewest removals are found with a dynamic program over "last kept level"
+//! and the current plateau length. A level can only follow one of the `k + 1`
+//! levels before it, so a report of `n` levels costs `O(n * k * plateau)`.
+//!
+//! [`Safety::explain`] turns this into a [`Verdict`]: which rule the report
+//! breaks first, and which levels to remove to fix it.
+
+use std::fmt;
+
+/// Direction the kept levels are heading in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonicity {
+    /// Every step goes the same way, equal levels are not allowed.
+    Strict,
+    /// No step goes against the direction, equal levels are allowed.
+    NonStrict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// The absolute difference between adjacent levels is within `min..=max`.
+    Delta {
+        min: i32,
+        max: i32,
+    },
+    Monotonic(Monotonicity),
+    /// At most this many equal levels in a row.
+    MaxPlateau(usize),
+}
+
+impl Rule {
+    /// Checks a step of `delta` that leaves a plateau of `plateau` equal levels.
+    fn check(&self, delta: i32, direction: Direction, plateau: usize) -> Result<(), ViolationKind> {
+        let follows_direction = match (self, direction) {
+            (Rule::Monotonic(Monotonicity::Strict), Direction::Increasing) => delta > 0,
+            (Rule::Monotonic(Monotonicity::Strict), Direction::Decreasing) => delta < 0,
+            (Rule::Monotonic(Monotonicity::NonStrict), Direction::Increasing) => delta >= 0,
+            (Rule::Monotonic(Monotonicity::NonStrict), Direction::Decreasing) => delta <= 0,
+            _ => true,
+        };
+
+        match self {
+            Rule::Delta { min, .. } if delta.abs() < *min => {
+                Err(ViolationKind::DeltaTooSmall { delta, min: *min })
+            }
+            Rule::Delta { max, .. } if delta.abs() > *max => {
+                Err(ViolationKind::DeltaTooLarge { delta, max: *max })
+            }
+            Rule::Monotonic(_) if !follows_direction => Err(ViolationKind::DirectionFlip {
+                expected: direction,
+                delta,
+            }),
+            Rule::MaxPlateau(max) if plateau > *max => Err(ViolationKind::Plateau {
+                length: plateau,
+                max: *max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn allows_step(&self, delta: i32, direction: Direction) -> bool {
+        self.check(delta, direction, 1).is_ok()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A report needs at least two levels.
+    TooShort,
+    DirectionFlip {
+        expected: Direction,
+        delta: i32,
+    },
+    DeltaTooSmall {
+        delta: i32,
+        min: i32,
+    },
+    DeltaTooLarge {
+        delta: i32,
+        max: i32,
+    },
+    Plateau {
+        length: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::TooShort => write!(f, "too short, at least two levels are needed"),
+            ViolationKind::DirectionFlip { expected, delta } => {
+                let expected = match expected {
+                    Direction::Increasing => "increasing",
+                    Direction::Decreasing => "decreasing",
+                };
+                write!(
+                    f,
+                    "direction flip, expected {expected} but changed by {delta}"
+                )
+            }
+            ViolationKind::DeltaTooSmall { delta, min } => {
+                write!(
+                    f,
+                    "delta too small, changed by {delta} but at least {min} needed"
+                )
+            }
+            ViolationKind::DeltaTooLarge { delta, max } => {
+                write!(
+                    f,
+                    "delta too large, changed by {delta} but at most {max} allowed"
+                )
+            }
+            ViolationKind::Plateau { length, max } => {
+                write!(f, "plateau of {length} equal levels, at most {max} allowed")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the level that breaks the rule.
+    pub index: usize,
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    /// Whether the report is safe within the dampener quota.
+    pub safe: bool,
+    /// The first rule the report breaks as is.
+    pub violation: Option<Violation>,
+    /// Fewest level indices to remove for the rest to follow the rules,
+    /// regardless of the dampener quota. `None` if no removals help.
+    pub removals: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    removals: usize,
+    /// The previous kept level and its plateau state.
+    previous: Option<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Safety {
+    pub rules: Vec<Rule>,
+    /// How many levels the problem dampener may remove.
+    pub dampener: usize,
+}
+
+impl Safety {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Strictly monotonic with adjacent levels 1 to 3 apart.
+    pub fn part_1() -> Self {
+        Self::new()
+            .with(Rule::Delta { min: 1, max: 3 })
+            .with(Rule::Monotonic(Monotonicity::Strict))
+    }
+
+    /// Part 1 with a dampener that removes a single level.
+    pub fn part_2() -> Self {
+        Self::part_1().with_dampener(1)
+    }
+
+    pub fn with(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_dampener(mut self, dampener: usize) -> Self {
+        self.dampener = dampener;
+        self
+    }
+
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        self.min_removals(report)
+            .is_some_and(|removals| removals <= self.dampener)
+    }
+
+    pub fn count_safe(&self, reports: &[Vec<i32>]) -> usize {
+        reports.iter().filter(|report| self.is_safe(report)).count()
+    }
+
+    /// Fewest levels to remove so the rest follows the rules, considering
+    /// removals up to the dampener quota only. `None` if that is not enough.
+    /// At least two levels have to remain for a report to be judged.
+    pub fn min_removals(&self, report: &[i32]) -> Option<usize> {
+        self.removal_set(report, self.dampener)
+            .map(|removals| removals.len())
+    }
+
+    pub fn explain(&self, report: &[i32]) -> Verdict {
+        let removals = self.removal_set(report, report.len());
+        Verdict {
+            safe: removals
+                .as_ref()
+                .is_some_and(|removals| removals.len() <= self.dampener),
+            violation: self.first_violation(report),
+            removals,
+        }
+    }
+
+    /// Walks the report as is, taking the direction from the first change.
+    fn first_violation(&self, report: &[i32]) -> Option<Violation> {
+        if report.len() < 2 {
+            return Some(Violation {
+                index: 0,
+                kind: ViolationKind::TooShort,
+            });
+        }
+        let direction = match report.windows(2).find(|pair| pair[0] != pair[1]) {
+            Some(pair) if pair[1] < pair[0] => Direction::Decreasing,
+            _ => Direction::Increasing,
+        };
+
+        let mut plateau = 1;
+        for (index, pair) in report.windows(2).enumerate() {
+            let delta = pair[1] - pair[0];
+            plateau = if delta == 0 { plateau + 1 } else { 1 };
+            for rule in &self.rules {
+                if let Err(kind) = rule.check(delta, direction, plateau) {
+                    return Some(Violation {
+                        index: index + 1,
+                        kind,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Indices of the fewest levels to remove, with at most `quota` removals.
+    fn removal_set(&self, report: &[i32], quota: usize) -> Option<Vec<usize>> {
+        let kept = [Direction::Increasing, Direction::Decreasing]
+            .into_iter()
+            .filter_map(|direction| self.fewest_removals(report, direction, quota))
+            .max_by_key(|kept| kept.len())?;
+        Some(
+            (0..report.len())
+                .filter(|index| !kept.contains(index))
+                .collect(),
+        )
+    }
+
+    fn max_plateau(&self) -> Option<usize> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Rule::MaxPlateau(max) => Some(*max),
+                _ => None,
+            })
+            .min()
+    }
+
+    fn allows_step(&self, delta: i32, direction: Direction) -> bool {
+        self.rules
+            .iter()
+            .all(|rule| rule.allows_step(delta, direction))
+    }
+
+    /// Indices of the longest kept sequence heading in `direction`.
+    fn fewest_removals(
+        &self,
+        report: &[i32],
+        direction: Direction,
+        quota: usize,
+    ) -> Option<Vec<usize>> {
+        let n = report.len();
+        if n < 2 {
+            return None;
+        }
+        let max_plateau = self.max_plateau();
+        if max_plateau == Some(0) {
+            return None;
+        }
+        // Without a plateau rule the plateau length does not matter, so a
+        // single state is tracked. No plateau is longer than the report.
+        let plateaus = max_plateau.map_or(1, |max| max.min(n));
+
+        // best[i][p - 1]: fewest removals among levels 0..=i for a kept
+        // sequence ending at level i, currently on a plateau of p levels.
+        let mut best = vec![vec![None::<State>; plateaus]; n];
+        let mut result: Option<(usize, usize, usize)> = None;
+
+        for i in 0..n {
+            if i <= quota {
+                best[i][0] = Some(State {
+                    removals: i,
+                    previous: None,
+                });
+            }
+            for j in i.saturating_sub(quota + 1)..i {
+                let delta = report[i] - report[j];
+                if !self.allows_step(delta, direction) {
+                    continue;
+                }
+                let skipped = i - j - 1;
+                for p in 0..plateaus {
+                    let Some(state) = best[j][p] else {
+                        continue;
+                    };
+                    let next = match (delta, max_plateau) {
+                        (0, Some(_)) => p + 1,
+                        _ => 0,
+                    };
+                    if next >= plateaus {
+                        continue;
+                    }
+                    let removals = state.removals + skipped;
+                    if removals <= quota
+                        && best[i][next].is_none_or(|best| removals < best.removals)
+                    {
+                        best[i][next] = Some(State {
+                            removals,
+                            previous: Some((j, p)),
+                        });
+                    }
+                }
+            }
+
+            let trailing = n - 1 - i;
+            // The kept sequence needs a step, i.e. at least two levels.
+            if n - trailing < 2 {
+                continue;
+            }
+            for (p, state) in best[i].iter().enumerate() {
+                let Some(state) = state else {
+                    continue;
+                };
+                let total = state.removals + trailing;
+                if total <= quota && total + 2 <= n && result.is_none_or(|best| total < best.0) {
+                    result = Some((total, i, p));
+                }
+            }
+        }
+
+        let (_, mut i, mut p) = result?;
+        let mut kept = vec![i];
+        while let Some((j, pj)) = best[i][p].and_then(|state| state.previous) {
+            kept.push(j);
+            (i, p) = (j, pj);
+        }
+        kept.reverse();
+        Some(kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ex_2_brute;
+
+    /// Tries every subset of removals, smallest first.
+    fn brute_force_min_removals(safety: &Safety, report: &[i32]) -> Option<usize> {
+        let n = report.len();
+        let follows_rules = |kept: &[i32]| {
+            kept.len() >= 2
+                && [Direction::Increasing, Direction::Decreasing]
+                    .into_iter()
+                    .any(|direction| {
+                        let steps_ok = kept
+                            .windows(2)
+                            .all(|pair| safety.allows_step(pair[1] - pair[0], direction));
+                        let plateau_ok = safety.max_plateau().is_none_or(|max| {
+                            kept.chunk_by(|a, b| a == b).all(|run| run.len() <= max)
+                        });
+                        steps_ok && plateau_ok
+                    })
+        };
+
+        (0..1u32 << n)
+            .filter(|mask| mask.count_ones() as usize <= safety.dampener)
+            .filter(|mask| {
+                let kept = (0..n)
+                    .filter(|i| mask & (1 << i) == 0)
+                    .map(|i| report[i])
+                    .collect::<Vec<_>>();
+                follows_rules(&kept)
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+    }
+
+    fn random_reports(seed: u64, count: usize, max_len: usize) -> Vec<Vec<i32>> {
+        let mut state = seed;
+        let mut next = move |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        (0..count)
+            .map(|_| {
+                let len = 3 + next(max_len as u64 - 2) as usize;
+                let mut level = 10 + next(20) as i32;
+                (0..len)
+                    .map(|_| {
+                        level += next(9) as i32 - 4;
+                        level
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_part_1_and_2_on_example() {
+        let reports = vec![
+            vec![7, 6, 4, 2, 1],
+            vec![1, 2, 7, 8, 9],
+            vec![9, 7, 6, 2, 1],
+            vec![1, 3, 2, 4, 5],
+            vec![8, 6, 4, 4, 1],
+            vec![1, 3, 6, 7, 9],
+        ];
+        assert_eq!(Safety::part_1().count_safe(&reports), 2);
+        assert_eq!(Safety::part_2().count_safe(&reports), 4);
+    }
+
+    #[test]
+    fn test_matches_ex_2_brute() {
+        let reports = random_reports(2, 2000, 8);
+        for report in reports {
+            let brute = ex_2_brute::count_safe_reports(&vec![report.clone()]) == 1;
+            assert_eq!(Safety::part_2().is_safe(&report), brute, "{report:?}");
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force_for_any_k() {
+        let plateau = Safety::new()
+            .with(Rule::Delta { min: 0, max: 2 })
+            .with(Rule::Monotonic(Monotonicity::NonStrict))
+            .with(Rule::MaxPlateau(2));
+        let unordered = Safety::new().with(Rule::Delta { min: 1, max: 4 });
+
+        for (seed, safety) in [(3, Safety::part_1()), (4, plateau), (5, unordered)] {
+            for k in 0..=4 {
+                let safety = safety.clone().with_dampener(k);
+                for report in random_reports(seed, 300, 9) {
+                    assert_eq!(
+                        safety.min_removals(&report),
+                        brute_force_min_removals(&safety, &report),
+                        "{safety:?} {report:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_removals() {
+        let safety = Safety::part_1().with_dampener(3);
+        assert_eq!(safety.min_removals(&[1, 2, 3]), Some(0));
+        assert_eq!(safety.min_removals(&[1, 2, 9, 3, 4]), Some(1));
+        assert_eq!(safety.min_removals(&[1, 9, 2, 8, 3]), Some(2));
+        assert_eq!(safety.min_removals(&[5, 5, 5, 5]), None);
+        assert_eq!(safety.min_removals(&[1]), None);
+    }
+
+    #[test]
+    fn test_explain() {
+        let verdict = Safety::part_2().explain(&[1, 3, 2, 4, 5]);
+        assert!(verdict.safe);
+        assert_eq!(
+            verdict.violation,
+            Some(Violation {
+                index: 2,
+                kind: ViolationKind::DirectionFlip {
+                    expected: Direction::Increasing,
+                    delta: -1
+                }
+            })
+        );
+        assert_eq!(verdict.removals, Some(vec![2]));
+
+        let verdict = Safety::part_2().explain(&[9, 7, 6, 2, 1]);
+        assert!(!verdict.safe);
+        assert_eq!(
+            verdict.violation.map(|violation| violation.kind),
+            Some(ViolationKind::DeltaTooLarge { delta: -4, max: 3 })
+        );
+        assert_eq!(verdict.removals, Some(vec![3, 4]));
+
+        let verdict = Safety::part_1().explain(&[8, 6, 4, 4, 1]);
+        assert_eq!(verdict.violation.map(|violation| violation.index), Some(3));
+        assert_eq!(verdict.removals, Some(vec![3]));
+        assert_eq!(Safety::part_1().explain(&[1, 2]).violation, None);
+    }
+
+    #[test]
+    fn test_explain_removals_are_minimal() {
+        for report in random_reports(6, 300, 9) {
+            let safety = Safety::part_1();
+            let verdict = safety.explain(&report);
+            let removals = verdict.removals.clone();
+            let unbounded = safety.clone().with_dampener(report.len());
+            assert_eq!(
+                removals.as_ref().map(Vec::len),
+                brute_force_min_removals(&unbounded, &report)
+            );
+            if let Some(removals) = removals {
+                let kept = (0..report.len())
+                    .filter(|i| !removals.contains(i))
+                    .map(|i| report[i])
+                    .collect::<Vec<_>>();
+                assert_eq!(safety.first_violation(&kept), None, "{report:?} {kept:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_plateau() {
+        let safety = Safety::new()
+            .with(Rule::Monotonic(Monotonicity::NonStrict))
+            .with(Rule::MaxPlateau(2));
+        assert!(safety.is_safe(&[1, 1, 2, 2, 3]));
+        assert!(!safety.is_safe(&[1, 1, 1, 2]));
+        assert!(safety.clone().with_dampener(1).is_safe(&[1, 1, 1, 2]));
+    }
+
+    #[test]
+    fn test_huge_plateau_limit() {
+        let safety = Safety::new()
+            .with(Rule::Monotonic(Monotonicity::NonStrict))
+            .with(Rule::MaxPlateau(usize::MAX));
+        assert!(safety.is_safe(&[1, 1, 1, 1, 2]));
+        assert!(!safety.is_safe(&[1, 1, 3, 2]));
+        assert!(safety.clone().with_dampener(1).is_safe(&[1, 1, 3, 2]));
+    }
+}
@@ -1,5 +1,6 @@
 use aoc_2024_lib::board::Board;
 use aoc_2024_lib::point2::Point2;
+use aoc_2024_lib::tracing::trace;
 use pathfinding::matrix::directions::DIRECTIONS_4;
 use pathfinding::prelude::bfs;
 use std::collections::HashSet;
@@ -39,12 +40,7 @@ pub fn find_all_cheats(input: &str, min_length: usize) -> Vec<Cheat> {
 
     // For each point on the path
     for (i, &point) in path.iter().enumerate() {
-        if cfg!(debug_assertions) {
-            println!(
-                "considering point {}/{} {:?}",
-                i, original_path_length, point
-            );
-        }
+        trace!(i, original_path_length, ?point, "considering point");
 
         // Check all possible cheat destinations in each direction
         for &dir in DIRECTIONS_4.iter() {
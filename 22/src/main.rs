@@ -1,6 +1,6 @@
-use std::fs;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::tracing::{debug, trace};
+use std::io;
 
 fn mix_and_prune(secret: i64) -> i64 {
     secret % 16777216
@@ -77,31 +77,26 @@ fn solve2(initial_secrets: &[i64]) -> i64 {
             for c in -9..=9 {
                 for d in -9..=9 {
                     let sequence = [a, b, c, d];
-                    println!("{:?}", sequence);
                     let sum = evaluate_sequence(initial_secrets, &sequence);
                     if sum > best_sum {
                         best_sum = sum;
                         best_sequence = sequence;
                     }
-                    println!("sum: {}", sum);
-                    println!("best_sum: {}", best_sum);
+                    trace!(?sequence, sum, best_sum);
                 }
             }
         }
     }
 
-    println!("Best sequence: {:?}", best_sequence);
+    debug!(?best_sequence, best_sum, "best sequence");
     best_sum
 }
 
-fn read_numbers<P: AsRef<Path>>(path: P) -> io::Result<Vec<i64>> {
-    let file = fs::File::open(path)?;
-    let reader = io::BufReader::new(file);
-
-    reader
+fn parse_numbers(content: &str) -> io::Result<Vec<i64>> {
+    content
         .lines()
         .map(|line| {
-            line?
+            line.trim()
                 .parse()
                 .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
         })
@@ -109,12 +104,24 @@ fn read_numbers<P: AsRef<Path>>(path: P) -> io::Result<Vec<i64>> {
 }
 
 fn main() -> io::Result<()> {
-    let initial_secrets = read_numbers("./input.txt")?;
-    let result1 = solve1(&initial_secrets);
-    println!("Part 1 - Sum of 2000th secret numbers: {}", result1);
-
-    let result2 = solve2(&initial_secrets);
-    println!("Part 2 - Maximum bananas: {}", result2);
+    let args = Args::from_env();
+    let reporter = args.reporter(22);
+
+    for input in args.load("./input.txt")? {
+        let initial_secrets = parse_numbers(&input.content)?;
+        if args.runs_part(1) {
+            let result1 = reporter.time(1, &input.name, || solve1(&initial_secrets));
+            reporter.emit(&result1, |r| {
+                format!("Part 1 - Sum of 2000th secret numbers: {}", r.answer)
+            });
+        }
+        if args.runs_part(2) {
+            let result2 = reporter.time(2, &input.name, || solve2(&initial_secrets));
+            reporter.emit(&result2, |r| {
+                format!("Part 2 - Maximum bananas: {}", r.answer)
+            });
+        }
+    }
     Ok(())
 }
 
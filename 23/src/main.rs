@@ -1,29 +1,23 @@
-use std::env;
-
 use anyhow::Result;
-use aoc_2024_lib::input_reader::{self, Input};
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::input_reader::Input;
 
 mod ex1;
 mod ex2;
 mod parse_input;
 
 fn main() -> Result<()> {
-    let inputs = input_reader::read_input("./inputs.md")?;
-
-    let args = env::args().collect::<Vec<_>>();
-    let part = args
-        .get(1)
-        .expect("Please provide the part number to run as first argument.");
+    let args = Args::from_env();
+    let reporter = args.reporter(23);
 
-    for Input { name, content } in inputs.sections {
-        if part == "1" {
-            let result = ex1::solve(&content);
-            println!("ex1\t{name}\t{result}");
-        } else if part == "2" {
-            let result = ex2::solve(&content);
-            println!("ex2\t{name}\t{result}");
-        } else {
-            panic!("Invalid part number: {part}");
+    for Input { name, content } in args.load("./inputs.md")? {
+        if args.runs_part(1) {
+            let result = reporter.time(1, &name, || ex1::solve(&content));
+            reporter.emit(&result, |r| format!("ex1\t{name}\t{}", r.answer));
+        }
+        if args.runs_part(2) {
+            let result = reporter.time(2, &name, || ex2::solve(&content));
+            reporter.emit(&result, |r| format!("ex2\t{name}\t{}", r.answer));
         }
     }
 
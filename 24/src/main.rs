@@ -1,31 +1,26 @@
-use std::env;
-
 use anyhow::Result;
-use aoc_2024_lib::input_reader::{self, Input};
+use aoc_2024_lib::cli::Args;
+use aoc_2024_lib::input_reader::Input;
 
 mod ex1;
 mod ex2;
 mod parse_input;
 
 fn main() -> Result<()> {
-    let inputs = input_reader::read_input("./inputs.md")?;
-
-    let args = env::args().collect::<Vec<_>>();
-    let part = args
-        .get(1)
-        .expect("Please provide the part number to run as first argument.");
+    let args = Args::from_env();
+    let reporter = args.reporter(24);
 
-    if part == "1" {
-        for Input { name, content } in inputs.sections {
-            let result = ex1::solve(&content);
-            println!("ex1\t{name}\t{result}");
+    if args.runs_part(1) {
+        for Input { name, content } in args.load("./inputs.md")? {
+            let result = reporter.time(1, &name, || ex1::solve(&content));
+            reporter.emit(&result, |r| format!("ex1\t{name}\t{}", r.answer));
+        }
+    }
+    if args.runs_part(2) {
+        for input in args.load_section("./inputs.md", "Input")? {
+            let result = reporter.time(2, &input.name, || ex2::solve(&input.content));
+            reporter.emit(&result, |r| format!("ex2\t{}", r.answer));
         }
-    } else if part == "2" {
-        let input = inputs.get_input("Input");
-        let result = ex2::solve(&input.content);
-        println!("ex2\t{result}");
-    } else {
-        panic!("Invalid part number: {part}");
     }
     Ok(())
 }
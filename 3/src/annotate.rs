@@ -0,0 +1,312 @@
+//! The input with every instruction highlighted, used by the `explain`
+//! binary.
+
+use crate::interpreter::{Interpreter, Status};
+use crate::lexer::InstructionSet;
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Counted towards the total.
+    Executed,
+    /// Valid, but in a disabled region.
+    Disabled,
+    /// An enable or disable instruction.
+    Control,
+    /// A near miss, see [`InstructionSet::near_miss_at`].
+    Malformed,
+}
+
+impl Kind {
+    const ALL: [Kind; 4] = [
+        Kind::Executed,
+        Kind::Disabled,
+        Kind::Control,
+        Kind::Malformed,
+    ];
+
+    fn ansi(self) -> &'static str {
+        match self {
+            Kind::Executed => "\x1b[1;32m",
+            Kind::Disabled => "\x1b[2;9m",
+            Kind::Control => "\x1b[36m",
+            Kind::Malformed => "\x1b[4;31m",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Kind::Executed => "executed",
+            Kind::Disabled => "disabled",
+            Kind::Control => "control",
+            Kind::Malformed => "malformed",
+        };
+        f.pad(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    pub span: Range<usize>,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotated {
+    pub total: u64,
+    /// In input order, without overlaps.
+    pub highlights: Vec<Highlight>,
+}
+
+impl Annotated {
+    pub fn count(&self, kind: Kind) -> usize {
+        self.highlights
+            .iter()
+            .filter(|highlight| highlight.kind == kind)
+            .count()
+    }
+
+    fn summary(&self) -> String {
+        let counts = Kind::ALL
+            .iter()
+            .map(|&kind| format!("{} {kind}", self.count(kind)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("total {}: {counts}", self.total)
+    }
+}
+
+/// Runs the input like [`crate::interpreter::run`], also picking up the
+/// near misses in between the instructions.
+pub fn annotate(set: &InstructionSet, input: &str) -> Annotated {
+    let input = input.as_bytes();
+    let mut interpreter = Interpreter::new(set);
+    let mut highlights = Vec::new();
+    let mut position = 0;
+
+    while position < input.len() {
+        let highlight = if let Some(token) = set.match_at(input, position) {
+            let kind = match interpreter.execute(&token).status {
+                Status::Executed { .. } => Kind::Executed,
+                Status::Skipped { .. } => Kind::Disabled,
+                Status::Control => Kind::Control,
+            };
+            Highlight {
+                span: token.span,
+                kind,
+            }
+        } else if let Some(span) = set.near_miss_at(input, position) {
+            Highlight {
+                span,
+                kind: Kind::Malformed,
+            }
+        } else {
+            position += 1;
+            continue;
+        };
+        position = highlight.span.end;
+        highlights.push(highlight);
+    }
+
+    Annotated {
+        total: interpreter.total(),
+        highlights,
+    }
+}
+
+/// The input with terminal colours: executed in bold green, disabled dimmed
+/// and struck through, control in cyan and malformed underlined in red.
+pub fn ansi(input: &str, annotated: &Annotated) -> String {
+    let mut out = String::with_capacity(input.len() * 2);
+    render(
+        input,
+        annotated,
+        &mut out,
+        |out, text| out.push_str(text),
+        |out, kind, text| {
+            out.push_str(kind.ansi());
+            out.push_str(text);
+            out.push_str("\x1b[0m");
+        },
+    );
+    out
+}
+
+/// One line per highlight, for when colours are not available.
+pub fn list(input: &str, annotated: &Annotated) -> String {
+    annotated
+        .highlights
+        .iter()
+        .map(|Highlight { span, kind }| {
+            let at = format!("{}..{}", span.start, span.end);
+            format!("{at:<16}  {kind:<9}  {}\n", &input[span.clone()])
+        })
+        .collect()
+}
+
+/// A self-contained HTML page with the highlighted input and a legend.
+pub fn html(title: &str, input: &str, annotated: &Annotated) -> String {
+    let mut body = String::with_capacity(input.len() * 2);
+    render(
+        input,
+        annotated,
+        &mut body,
+        escape_into,
+        |out, kind, text| {
+            out.push_str(&format!(r#"<span class="{kind}" title="{kind}">"#));
+            escape_into(out, text);
+            out.push_str("</span>");
+        },
+    );
+
+    let legend = Kind::ALL
+        .iter()
+        .map(|kind| format!(r#"<span class="{kind}">{kind}</span>"#))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut title_html = String::new();
+    escape_into(&mut title_html, title);
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title_html}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+pre {{ white-space: pre-wrap; word-break: break-all; font-size: 13px; color: #888; }}
+.executed {{ color: #0a0; font-weight: bold; }}
+.disabled {{ color: #aaa; text-decoration: line-through; }}
+.control {{ color: #08c; }}
+.malformed {{ color: #c00; text-decoration: underline wavy; }}
+</style>
+</head>
+<body>
+<h1>{title_html}</h1>
+<p>{}</p>
+<p>{legend}</p>
+<pre>{body}</pre>
+</body>
+</html>
+"#,
+        annotated.summary()
+    )
+}
+
+fn render(
+    input: &str,
+    annotated: &Annotated,
+    out: &mut String,
+    plain: impl Fn(&mut String, &str),
+    highlighted: impl Fn(&mut String, Kind, &str),
+) {
+    let mut position = 0;
+    for Highlight { span, kind } in &annotated.highlights {
+        plain(out, &input[position..span.start]);
+        highlighted(out, *kind, &input[span.clone()]);
+        position = span.end;
+    }
+    plain(out, &input[position..]);
+}
+
+fn escape_into(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::run;
+
+    const EXAMPLE_2: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_annotate_example_2() {
+        let annotated = annotate(&InstructionSet::part_2(), EXAMPLE_2);
+        assert_eq!(annotated.total, 48);
+
+        let highlights = annotated
+            .highlights
+            .iter()
+            .map(|highlight| (&EXAMPLE_2[highlight.span.clone()], highlight.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            highlights,
+            vec![
+                ("mul(2,4)", Kind::Executed),
+                ("mul[3,7]", Kind::Malformed),
+                ("don't()", Kind::Control),
+                ("mul(5,5)", Kind::Disabled),
+                ("mul(32,64]", Kind::Malformed),
+                ("mul(11,8)", Kind::Disabled),
+                ("do()", Kind::Control),
+                ("mul(8,5)", Kind::Executed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotate_matches_run() {
+        let input = "mul(1,2)mul(1234,5)mul[1,1]mul(3,4)don't()mul(1,mul(2,3)do()mul(5,6)";
+        let set = InstructionSet::part_2();
+        let annotated = annotate(&set, input);
+        let execution = run(&set, input.as_bytes());
+        assert_eq!(annotated.total, execution.total);
+        let valid = annotated
+            .highlights
+            .iter()
+            .filter(|highlight| highlight.kind != Kind::Malformed)
+            .map(|highlight| highlight.span.clone())
+            .collect::<Vec<_>>();
+        let spans = execution
+            .steps
+            .iter()
+            .map(|step| step.span.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(valid, spans);
+        assert_eq!(annotated.count(Kind::Malformed), 2);
+    }
+
+    #[test]
+    fn test_ansi() {
+        let input = "a mul(2,3) mul[1,1] don't() mul(1,1)";
+        let annotated = annotate(&InstructionSet::part_2(), input);
+        assert_eq!(
+            ansi(input, &annotated),
+            "a \x1b[1;32mmul(2,3)\x1b[0m \x1b[4;31mmul[1,1]\x1b[0m \x1b[36mdon't()\x1b[0m \x1b[2;9mmul(1,1)\x1b[0m"
+        );
+        assert_eq!(
+            list(input, &annotated),
+            "2..10             executed   mul(2,3)\n\
+             11..19            malformed  mul[1,1]\n\
+             20..27            control    don't()\n\
+             28..36            disabled   mul(1,1)\n"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let input = "<b>mul(2,3)&don't()mul(1,1)";
+        let annotated = annotate(&InstructionSet::part_2(), input);
+        let page = html("input.txt", input, &annotated);
+        assert!(page.contains(
+            r#"<pre>&lt;b&gt;<span class="executed" title="executed">mul(2,3)</span>&amp;<span class="control" title="control">don&#39;t()</span><span class="disabled" title="disabled">mul(1,1)</span></pre>"#
+        ));
+        assert!(page.contains("<p>total 6: 1 executed, 1 disabled, 1 control, 0 malformed</p>"));
+        assert!(!page.contains("<script") && !page.contains("http"));
+    }
+}
@@ -0,0 +1,36 @@
+//! Shows which instructions counted: the input is printed with executed,
+//! disabled and malformed instructions highlighted, and also written to a
+//! self-contained `<input>.html` page: `cargo run --bin explain -- --part 2`.
+
+use aoc_2024_3::annotate::{annotate, ansi, html, list};
+use aoc_2024_3::lexer::InstructionSet;
+use aoc_2024_lib::cli::Args;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let set = match args.part {
+        Some(1) => InstructionSet::part_1(),
+        _ => InstructionSet::part_2(),
+    };
+
+    for input in args.load("./input.txt")? {
+        let annotated = annotate(&set, &input.content);
+        if io::stdout().is_terminal() {
+            println!("{}", ansi(&input.content, &annotated));
+        } else {
+            print!("{}", list(&input.content, &annotated));
+        }
+
+        let stem = Path::new(&input.name).file_stem().map_or_else(
+            || input.name.clone(),
+            |stem| stem.to_string_lossy().to_string(),
+        );
+        let path = format!("{stem}.html");
+        std::fs::write(&path, html(&input.name, &input.content, &annotated))?;
+        eprintln!("{}: total {}, wrote {path}", input.name, annotated.total);
+    }
+
+    Ok(())
+}
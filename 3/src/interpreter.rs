@@ -0,0 +1,163 @@
+//! Runs lexed instructions, keeping track of which ones count.
+//!
+//! The [`Interpreter`] is fed one token at a time, so its state can carry
+//! over between separately lexed pieces of input.
+
+use crate::lexer::{Effect, InstructionSet, Scopes, Token};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// An arithmetic instruction that added `value` to the total.
+    Executed { value: u64 },
+    /// An arithmetic instruction in a disabled region, which would have
+    /// added `value`.
+    Skipped { value: u64 },
+    /// An enable or disable instruction.
+    Control,
+}
+
+impl Status {
+    /// What the instruction adds when enabled, zero for control instructions.
+    pub fn value(&self) -> u64 {
+        match *self {
+            Status::Executed { value } | Status::Skipped { value } => value,
+            Status::Control => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub instruction: usize,
+    pub span: Range<usize>,
+    pub status: Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    pub total: u64,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Interpreter<'a> {
+    set: &'a InstructionSet,
+    /// Number of disables in effect, enabled when zero.
+    disabled: usize,
+    total: u64,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(set: &'a InstructionSet) -> Self {
+        Self {
+            set,
+            disabled: 0,
+            total: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.disabled == 0
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn execute(&mut self, token: &Token) -> Step {
+        let status = match self.set.instructions[token.instruction].effect {
+            Effect::Enable => {
+                self.disabled = match self.set.scopes {
+                    Scopes::Flat => 0,
+                    Scopes::Nested => self.disabled.saturating_sub(1),
+                };
+                Status::Control
+            }
+            Effect::Disable => {
+                self.disabled = match self.set.scopes {
+                    Scopes::Flat => 1,
+                    Scopes::Nested => self.disabled + 1,
+                };
+                Status::Control
+            }
+            Effect::Mul => self.accumulate(token.operands.iter().map(|&a| u64::from(a)).product()),
+            Effect::Add => self.accumulate(token.operands.iter().map(|&a| u64::from(a)).sum()),
+        };
+
+        Step {
+            instruction: token.instruction,
+            span: token.span.clone(),
+            status,
+        }
+    }
+
+    fn accumulate(&mut self, value: u64) -> Status {
+        if !self.is_enabled() {
+            return Status::Skipped { value };
+        }
+        self.total += value;
+        Status::Executed { value }
+    }
+}
+
+/// Lexes and runs the whole input.
+pub fn run(set: &InstructionSet, input: &[u8]) -> Execution {
+    let mut interpreter = Interpreter::new(set);
+    let steps = set
+        .lex(input)
+        .iter()
+        .map(|token| interpreter.execute(token))
+        .collect();
+    Execution {
+        total: interpreter.total(),
+        steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_2: &[u8] =
+        b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_run_example_2() {
+        let execution = run(&InstructionSet::part_2(), EXAMPLE_2);
+        assert_eq!(execution.total, 48);
+
+        let statuses = execution
+            .steps
+            .iter()
+            .map(|step| (step.span.clone(), step.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                (1..9, Status::Executed { value: 8 }),
+                (20..27, Status::Control),
+                (28..36, Status::Skipped { value: 25 }),
+                (48..57, Status::Skipped { value: 88 }),
+                (59..63, Status::Control),
+                (64..72, Status::Executed { value: 40 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        let input = b"don't()don't()mul(1,2)do()mul(3,4)do()mul(5,6)";
+        let flat = InstructionSet::part_2();
+        assert_eq!(run(&flat, input).total, 12 + 30);
+
+        let nested = InstructionSet::part_2().with_scopes(Scopes::Nested);
+        assert_eq!(run(&nested, input).total, 30);
+    }
+
+    #[test]
+    fn test_add() {
+        let set = InstructionSet::part_1().with("add", 2, Effect::Add);
+        assert_eq!(run(&set, b"add(2,3)mul(2,3)").total, 11);
+    }
+}
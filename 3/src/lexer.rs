@@ -0,0 +1,267 @@
+//! Lexer for the corrupted memory instruction language.
+//!
+//! An instruction is a name, `(`, a fixed number of comma separated operands
+//! of 1 to 3 digits each, and `)`, with nothing in between. Anything else is
+//! noise. Like the original regex, matching is attempted at every byte, so
+//! `undo()` contains a `do()`.
+
+use std::ops::Range;
+
+pub const MAX_OPERAND_DIGITS: usize = 3;
+/// Keeps the product of the operands within `u64`.
+const MAX_ARITY: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Adds the product of the operands to the total.
+    Mul,
+    /// Adds the sum of the operands to the total.
+    Add,
+    Enable,
+    Disable,
+}
+
+/// How `Enable` and `Disable` interact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scopes {
+    /// Each instruction switches the state, as in the puzzle.
+    #[default]
+    Flat,
+    /// Disables nest, every `Disable` needs its own `Enable`.
+    Nested,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub name: String,
+    pub arity: usize,
+    pub effect: Effect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InstructionSet {
+    pub instructions: Vec<Instruction>,
+    pub scopes: Scopes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Index into [`InstructionSet::instructions`].
+    pub instruction: usize,
+    pub operands: Vec<u32>,
+    /// Byte span in the input.
+    pub span: Range<usize>,
+}
+
+impl InstructionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `mul(a,b)` only.
+    pub fn part_1() -> Self {
+        Self::new().with("mul", 2, Effect::Mul)
+    }
+
+    /// `mul(a,b)`, `do()` and `don't()`.
+    pub fn part_2() -> Self {
+        Self::part_1()
+            .with("do", 0, Effect::Enable)
+            .with("don't", 0, Effect::Disable)
+    }
+
+    pub fn with(mut self, name: &str, arity: usize, effect: Effect) -> Self {
+        assert!(
+            arity <= MAX_ARITY,
+            "{name} takes {arity} operands, at most {MAX_ARITY} are supported"
+        );
+        self.instructions.push(Instruction {
+            name: name.to_string(),
+            arity,
+            effect,
+        });
+        self
+    }
+
+    pub fn with_scopes(mut self, scopes: Scopes) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// All instructions in the input, in order and without overlaps.
+    pub fn lex(&self, input: &[u8]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        while position < input.len() {
+            match self.match_at(input, position) {
+                Some(token) => {
+                    position = token.span.end;
+                    tokens.push(token);
+                }
+                None => position += 1,
+            }
+        }
+        tokens
+    }
+
+    /// The longest possible instruction, in bytes.
+    pub fn max_len(&self) -> usize {
+        self.instructions
+            .iter()
+            .map(|instruction| {
+                let operands = instruction.arity * MAX_OPERAND_DIGITS;
+                let commas = instruction.arity.saturating_sub(1);
+                instruction.name.len() + 2 + operands + commas
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The instruction starting exactly at `position`, if any.
+    pub fn match_at(&self, input: &[u8], position: usize) -> Option<Token> {
+        self.instructions
+            .iter()
+            .enumerate()
+            .find_map(|(index, instruction)| {
+                let (operands, end) = match_instruction(input, position, instruction)?;
+                Some(Token {
+                    instruction: index,
+                    operands,
+                    span: position..end,
+                })
+            })
+    }
+
+    /// A malformed instruction starting at `position`: a known name followed
+    /// by a bracketed list of digits, commas and spaces that is not valid,
+    /// such as `mul[3,7]`, `mul(32,64]` or `mul(1234,5)`. Only meaningful
+    /// where [`InstructionSet::match_at`] found nothing.
+    pub fn near_miss_at(&self, input: &[u8], position: usize) -> Option<Range<usize>> {
+        let max_len = self.max_len();
+        self.instructions.iter().find_map(|instruction| {
+            let open = position + instruction.name.len();
+            if input.get(position..open) != Some(instruction.name.as_bytes())
+                || !matches!(input.get(open), Some(b'(' | b'[' | b'{'))
+            {
+                return None;
+            }
+            let body = input[open + 1..]
+                .iter()
+                .take(max_len)
+                .take_while(|&&byte| byte.is_ascii_digit() || byte == b',' || byte == b' ')
+                .count();
+            let close = open + 1 + body;
+            matches!(input.get(close), Some(b')' | b']' | b'}')).then_some(position..close + 1)
+        })
+    }
+}
+
+fn match_instruction(
+    input: &[u8],
+    position: usize,
+    instruction: &Instruction,
+) -> Option<(Vec<u32>, usize)> {
+    let mut cursor = position;
+    let mut expect = |expected: &[u8]| {
+        let matches = input.get(cursor..cursor + expected.len()) == Some(expected);
+        cursor += expected.len();
+        matches
+    };
+    if !expect(instruction.name.as_bytes()) || !expect(b"(") {
+        return None;
+    }
+
+    let mut operands = Vec::with_capacity(instruction.arity);
+    for index in 0..instruction.arity {
+        if index > 0 {
+            if input.get(cursor) != Some(&b',') {
+                return None;
+            }
+            cursor += 1;
+        }
+        let digits = input[cursor.min(input.len())..]
+            .iter()
+            .take(MAX_OPERAND_DIGITS + 1)
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        if !(1..=MAX_OPERAND_DIGITS).contains(&digits) {
+            return None;
+        }
+        let operand = input[cursor..cursor + digits]
+            .iter()
+            .fold(0, |value, digit| value * 10 + u32::from(digit - b'0'));
+        operands.push(operand);
+        cursor += digits;
+    }
+
+    if input.get(cursor) != Some(&b')') {
+        return None;
+    }
+    Some((operands, cursor + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lex_example() {
+        let input = b"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        let tokens = InstructionSet::part_1().lex(input);
+        let operands = tokens
+            .iter()
+            .map(|token| token.operands.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operands,
+            vec![vec![2, 4], vec![5, 5], vec![11, 8], vec![8, 5]]
+        );
+        assert_eq!(tokens[0].span, 1..9);
+        assert_eq!(&input[tokens[2].span.clone()], b"mul(11,8)");
+    }
+
+    #[test]
+    fn test_operand_digits() {
+        let set = InstructionSet::part_1();
+        assert_eq!(set.lex(b"mul(123,4)").len(), 1);
+        assert!(set.lex(b"mul(1234,5)").is_empty());
+        assert!(set.lex(b"mul(,5)").is_empty());
+        assert!(set.lex(b"mul(1, 5)").is_empty());
+        assert!(set.lex(b"mul(1,5").is_empty());
+    }
+
+    #[test]
+    fn test_control_instructions() {
+        let set = InstructionSet::part_2();
+        let tokens = set.lex(b"undo()?don't()do(1)");
+        let names = tokens
+            .iter()
+            .map(|token| set.instructions[token.instruction].name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["do", "don't"]);
+        assert_eq!(tokens[0].span, 2..6);
+    }
+
+    #[test]
+    fn test_near_misses() {
+        let set = InstructionSet::part_2();
+        assert_eq!(set.near_miss_at(b"mul[3,7]!", 0), Some(0..8));
+        assert_eq!(set.near_miss_at(b"mul(32,64](", 0), Some(0..10));
+        assert_eq!(set.near_miss_at(b"mul(1234,5)", 0), Some(0..11));
+        assert_eq!(set.near_miss_at(b"mul( 2, 4 )", 0), Some(0..11));
+        assert_eq!(set.near_miss_at(b"do(1)", 0), Some(0..5));
+        assert_eq!(set.near_miss_at(b"mul(2,4", 0), None);
+        assert_eq!(set.near_miss_at(b"mul(x)", 0), None);
+        assert_eq!(set.near_miss_at(b"don't", 0), None);
+        assert_eq!(set.near_miss_at(b"xmul[1]", 0), None);
+    }
+
+    #[test]
+    fn test_custom_instructions() {
+        let set = InstructionSet::new().with("add", 3, Effect::Add);
+        let tokens = set.lex(b"add(1,2)add(1,2,3)");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].operands, vec![1, 2, 3]);
+        assert_eq!(set.max_len(), "add(999,999,999)".len());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod annotate;
+pub mod interpreter;
+pub mod lexer;
+pub mod scanner;
@@ -1,54 +1,50 @@
-use regex::Regex;
+use aoc_2024_3::interpreter::run;
+use aoc_2024_3::lexer::InstructionSet;
+use aoc_2024_lib::cli::Args;
 
-static EXAMPLE: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
-
-static EXAMPLE_2: &str =
-    "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-
-fn solve_ex1(input: &str) -> Option<u32> {
-    let pattern = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+fn solve_ex1(input: &str) -> u64 {
+    run(&InstructionSet::part_1(), input.as_bytes()).total
+}
 
-    pattern
-        .captures_iter(input)
-        .map(|captures| {
-            let a = captures[1].parse::<u32>()?;
-            let b = captures[2].parse::<u32>()?;
-            Ok::<u32, std::num::ParseIntError>(a * b)
-        })
-        .filter_map(|val: Result<u32, _>| val.ok())
-        .reduce(|acc, val| acc + val)
+fn solve_ex2(input: &str) -> u64 {
+    run(&InstructionSet::part_2(), input.as_bytes()).total
 }
 
-fn solve_ex2(input: &str) -> u32 {
-    let pattern = Regex::new(r"mul\((\d+),(\d+)\)|don't\(\)|do\(\)").unwrap();
-
-    let mut res = 0;
-    let mut enabled = true;
-
-    for operation in pattern.captures_iter(input) {
-        match operation.get(0).unwrap().as_str() {
-            "don't()" => enabled = false,
-            "do()" => enabled = true,
-            _ => {
-                if enabled {
-                    let a = operation[1].parse::<u32>().unwrap();
-                    let b = operation[2].parse::<u32>().unwrap();
-                    res += a * b;
-                }
-            }
+fn main() -> std::io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(3);
+
+    for input in args.load("input.txt")? {
+        if args.runs_part(1) {
+            let record = reporter.time(1, &input.name, || solve_ex1(&input.content));
+            reporter.emit(&record, |r| r.answer.clone());
+        }
+        if args.runs_part(2) {
+            let record = reporter.time(2, &input.name, || solve_ex2(&input.content));
+            reporter.emit(&record, |r| r.answer.clone());
         }
     }
 
-    res
+    Ok(())
 }
 
-fn main() {
-    println!("{:#?}", solve_ex1(EXAMPLE).unwrap());
-    println!("{:#?}", EXAMPLE_2);
-    println!("{:#?}", solve_ex2(EXAMPLE_2));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let input = std::fs::read_to_string("input.txt").unwrap();
+    static EXAMPLE: &str =
+        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
 
-    println!("{:#?}", solve_ex1(&input).unwrap());
-    println!("{:#?}", solve_ex2(&input));
+    static EXAMPLE_2: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve_ex1(EXAMPLE), 161);
+    }
+
+    #[test]
+    fn test_example_2() {
+        assert_eq!(solve_ex2(EXAMPLE_2), 48);
+    }
 }
@@ -0,0 +1,182 @@
+//! Day 3 over a [`Read`] of any size, in bounded memory.
+//!
+//! The input is read in chunks. Positions close enough to the end of the
+//! buffer that an instruction starting there could continue in the next
+//! chunk are only tried once more input has arrived, so at most
+//! [`InstructionSet::max_len`] bytes are carried over between chunks. The
+//! [`Interpreter`] lives across chunks, and so does the enable state.
+
+use crate::interpreter::{Interpreter, Step};
+use crate::lexer::InstructionSet;
+use aoc_2024_lib::rng::Rng;
+use std::io::{self, Read, Write};
+
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    set: &'a InstructionSet,
+    chunk_size: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(set: &'a InstructionSet) -> Self {
+        Self {
+            set,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Runs every instruction in `reader`, passing each step with spans
+    /// relative to the start of the stream to `on_step`. Returns the total.
+    pub fn scan(&self, mut reader: impl Read, mut on_step: impl FnMut(Step)) -> io::Result<u64> {
+        let window = self.set.max_len().max(1);
+        let mut interpreter = Interpreter::new(self.set);
+        let mut chunk = vec![0; self.chunk_size];
+        let mut buffer = Vec::with_capacity(self.chunk_size + window);
+        // Offset of `buffer[0]` in the stream.
+        let mut base = 0;
+        let mut position = 0;
+
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            buffer.extend_from_slice(&chunk[..read]);
+
+            let eof = read == 0;
+            let limit = if eof {
+                buffer.len()
+            } else {
+                (buffer.len() + 1).saturating_sub(window)
+            };
+            while position < limit {
+                match self.set.match_at(&buffer, position) {
+                    Some(mut token) => {
+                        position = token.span.end;
+                        token.span = token.span.start + base..token.span.end + base;
+                        on_step(interpreter.execute(&token));
+                    }
+                    None => position += 1,
+                }
+            }
+
+            if eof {
+                return Ok(interpreter.total());
+            }
+            buffer.drain(..position);
+            base += position;
+            position = 0;
+        }
+    }
+
+    pub fn total(&self, reader: impl Read) -> io::Result<u64> {
+        self.scan(reader, |_| ())
+    }
+}
+
+/// Writes at least `bytes` bytes of corrupted memory, reproducible by `seed`.
+///
+/// Besides valid instructions it contains near misses such as `mul(1234,5)`,
+/// `mul[3,7]` and `undo()`.
+pub fn write_generated(out: &mut impl Write, bytes: u64, seed: u64) -> io::Result<()> {
+    const NOISE: &[u8] = b"x%&!@^*_+-?<>[]{} ,'()#$;:/whyselectfromwhenhowmulo";
+
+    let mut rng = Rng::new(seed);
+    let mut next = |bound: u64| rng.below(bound);
+
+    let mut written = 0;
+    while written < bytes {
+        let fragment = match next(16) {
+            0..=2 => format!("mul({},{})", next(1000), next(1000)),
+            3 => format!("mul({},{})", 1000 + next(9000), next(100)),
+            4 => "do()".to_string(),
+            5 => "don't()".to_string(),
+            6 => "undo()".to_string(),
+            7 => format!("mul[{},{}]", next(100), next(100)),
+            8 => format!("mul({},{}]", next(100), next(100)),
+            _ => String::from(char::from(NOISE[next(NOISE.len() as u64) as usize])),
+        };
+        out.write_all(fragment.as_bytes())?;
+        written += fragment.len() as u64;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::run;
+    use crate::lexer::{Effect, Scopes};
+
+    fn generated(bytes: u64, seed: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_generated(&mut out, bytes, seed).unwrap();
+        out
+    }
+
+    fn assert_matches_in_memory(set: &InstructionSet, input: &[u8]) {
+        let expected = run(set, input);
+        for chunk_size in [1, 2, 3, 7, 64, 4096] {
+            let mut steps = Vec::new();
+            let total = Scanner::new(set)
+                .with_chunk_size(chunk_size)
+                .scan(input, |step| steps.push(step))
+                .unwrap();
+            assert_eq!(total, expected.total, "chunk size {chunk_size}");
+            assert_eq!(steps, expected.steps, "chunk size {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn test_example() {
+        let input = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(
+            Scanner::new(&InstructionSet::part_2())
+                .total(&input[..])
+                .unwrap(),
+            48
+        );
+        assert_matches_in_memory(&InstructionSet::part_2(), input);
+    }
+
+    #[test]
+    fn test_generated() {
+        for seed in 0..4 {
+            let input = generated(20_000, seed);
+            assert_matches_in_memory(&InstructionSet::part_1(), &input);
+            assert_matches_in_memory(&InstructionSet::part_2(), &input);
+            assert_matches_in_memory(
+                &InstructionSet::part_2().with_scopes(Scopes::Nested),
+                &input,
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_instructions() {
+        let set = InstructionSet::part_2().with("add", 6, Effect::Add);
+        let input = b"add(1,2,3,4,5,6)don't()add(999,999,999,999,999,999)do()add(1,1,1,1,1,1)";
+        assert_matches_in_memory(&set, input);
+    }
+
+    #[test]
+    fn test_empty() {
+        let set = InstructionSet::part_2();
+        assert_eq!(Scanner::new(&set).total(io::empty()).unwrap(), 0);
+        assert_eq!(
+            Scanner::new(&InstructionSet::new())
+                .total(&b"mul(1,2)"[..])
+                .unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Day 3 in bounded memory, see [`aoc_2024_3::scanner`].
+//!
+//! `cargo run --release --bin stream -- --input big.txt`, where `big.txt` can
+//! come from `cargo run --release --bin generate -- 1000000000 > big.txt`.
+
+use aoc_2024_3::lexer::InstructionSet;
+use aoc_2024_3::scanner::Scanner;
+use aoc_2024_lib::cli::Args;
+use std::io;
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(3);
+    let (name, reader) = args.open("./input.txt")?;
+
+    // One pass serves both parts: part 1 counts every mul, enabled or not.
+    let set = InstructionSet::part_2();
+    let mut all = 0;
+    let record_2 = reporter.try_time(2, &name, || {
+        Scanner::new(&set).scan(reader, |step| all += step.status.value())
+    })?;
+
+    if args.runs_part(1) {
+        // The scan that found the total was the one timed for part 2.
+        let record = reporter.record(1, &name, all, record_2.duration());
+        reporter.emit(&record, |r| r.answer.clone());
+    }
+    if args.runs_part(2) {
+        reporter.emit(&record_2, |r| r.answer.clone());
+    }
+
+    Ok(())
+}
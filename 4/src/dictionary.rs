@@ -0,0 +1,148 @@
+//! Aho-Corasick automaton over a set of words, so that every line of the
+//! grid is read once per direction no matter how many words are searched.
+
+use crate::grid::Grid;
+use crate::search::{Match, Orientation, Position};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: HashMap<char, usize>,
+    /// Longest proper suffix of this node that is also in the trie.
+    fail: usize,
+    /// Words ending here, including through `fail`.
+    outputs: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    nodes: Vec<Node>,
+    lengths: Vec<usize>,
+}
+
+impl Dictionary {
+    pub fn new<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut nodes = vec![Node::default()];
+        let mut lengths = Vec::new();
+
+        for (index, word) in words.into_iter().enumerate() {
+            let mut node = 0;
+            for letter in word.chars() {
+                node = match nodes[node].children.get(&letter) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(letter, child);
+                        child
+                    }
+                };
+            }
+            // The root would match everywhere, empty words are never found.
+            if node != 0 {
+                nodes[node].outputs.push(index);
+            }
+            lengths.push(word.chars().count());
+        }
+
+        let mut queue = nodes[0].children.values().copied().collect::<VecDeque<_>>();
+        while let Some(node) = queue.pop_front() {
+            let children = nodes[node]
+                .children
+                .iter()
+                .map(|(&letter, &child)| (letter, child))
+                .collect::<Vec<_>>();
+            for (letter, child) in children {
+                let fail = if node == 0 {
+                    0
+                } else {
+                    Self::step(&nodes, nodes[node].fail, letter)
+                };
+                nodes[child].fail = fail;
+                let inherited = nodes[fail].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Self { nodes, lengths }
+    }
+
+    fn step(nodes: &[Node], mut node: usize, letter: char) -> usize {
+        loop {
+            if let Some(&child) = nodes[node].children.get(&letter) {
+                return child;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = nodes[node].fail;
+        }
+    }
+
+    /// Every occurrence of every word, reading each line of the grid in all
+    /// [`Orientation::directions`]. [`Match::pattern`] is the word's index.
+    pub fn scan(&self, grid: &Grid<char>) -> Vec<Match> {
+        let mut matches = Vec::new();
+        if self.nodes.len() == 1 {
+            return matches;
+        }
+
+        for orientation in Orientation::directions() {
+            let (dr, dc) = orientation.right;
+            let starts = grid
+                .cells()
+                .filter(|&(row, col, _)| !grid.contains(row - dr, col - dc));
+            for (row, col, _) in starts {
+                let line = (0..)
+                    .map(|step| (row + step * dr, col + step * dc))
+                    .take_while(|&(r, c)| grid.contains(r, c))
+                    .collect::<Vec<Position>>();
+
+                let mut node = 0;
+                for (end, &(r, c)) in line.iter().enumerate() {
+                    node = Self::step(&self.nodes, node, *grid.get(r, c));
+                    for &word in &self.nodes[node].outputs {
+                        let cells = line[end + 1 - self.lengths[word]..=end].to_vec();
+                        matches.push(Match {
+                            pattern: word,
+                            anchor: cells[0],
+                            orientation,
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::input_to_grid;
+
+    #[test]
+    fn test_scan_suffixes() {
+        // `she` contains `he`, and `hers` starts with it.
+        let grid = input_to_grid("ushers", '.');
+        let dictionary = Dictionary::new(["he", "she", "his", "hers"]);
+        let mut found = dictionary
+            .scan(&grid)
+            .into_iter()
+            .map(|m| (m.pattern, m.anchor))
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found, vec![(0, (0, 2)), (1, (0, 1)), (3, (0, 2))]);
+    }
+
+    #[test]
+    fn test_scan_directions() {
+        let grid = input_to_grid("AB\nBA", '.');
+        let dictionary = Dictionary::new(["AB"]);
+        // Two horizontal, two vertical, none on the diagonals.
+        assert_eq!(dictionary.scan(&grid).len(), 4);
+        assert!(Dictionary::new([""]).scan(&grid).is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub struct Grid<T> {
+    data: Vec<Vec<T>>,
+    width: usize,
+    height: usize,
+    default: T,
+}
+
+impl<T: Clone + Display> Grid<T> {
+    pub fn new(data: Vec<Vec<T>>, default: T) -> Self {
+        let height = data.len();
+        let width = if height > 0 { data[0].len() } else { 0 };
+        Self {
+            data,
+            width,
+            height,
+            default,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, row: isize, col: isize) -> bool {
+        row >= 0 && col >= 0 && row < self.height as isize && col < self.width as isize
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> &T {
+        if self.contains(row, col) {
+            &self.data[row as usize][col as usize]
+        } else {
+            &self.default
+        }
+    }
+
+    /// A grid of the same shape with every cell mapped by `f`.
+    pub fn map<U: Clone + Display>(
+        &self,
+        default: U,
+        f: impl Fn(isize, isize, &T) -> U,
+    ) -> Grid<U> {
+        let data = (0..self.height)
+            .map(|row| {
+                (0..self.width)
+                    .map(|col| f(row as isize, col as isize, &self.data[row][col]))
+                    .collect()
+            })
+            .collect();
+        Grid::new(data, default)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (isize, isize, &T)> {
+        (0..self.height).flat_map(move |row| {
+            (0..self.width).map(move |col| (row as isize, col as isize, &self.data[row][col]))
+        })
+    }
+}
+
+impl<T: Clone + Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut result = String::new();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                result.push_str(&self.data[row][col].to_string());
+            }
+            if row < self.height - 1 {
+                result.push('\n');
+            }
+        }
+        write!(f, "{}", result)
+    }
+}
+
+pub fn input_to_grid(input: &str, default: char) -> Grid<char> {
+    Grid::new(
+        input
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect())
+            .collect(),
+        default,
+    )
+}
@@ -0,0 +1,44 @@
+use grid::Grid;
+use search::{Pattern, Position, Search};
+use std::collections::BTreeSet;
+
+pub mod dictionary;
+pub mod grid;
+pub mod mask;
+pub mod search;
+
+/// `XMAS` in any direction.
+pub fn part_1() -> Search {
+    Search::new().with_words(&["XMAS"])
+}
+
+/// Two `MAS` crossing in an X.
+pub fn part_2() -> Search {
+    Search::new().with_pattern(Pattern::parse(
+        "
+        M.S
+        .A.
+        M.S
+        ",
+    ))
+}
+
+/// Number of [`part_1`] matches.
+pub fn solve_ex1(grid: &Grid<char>) -> usize {
+    part_1().count(grid)
+}
+
+/// Number of [`part_2`] matches.
+pub fn solve_ex2(grid: &Grid<char>) -> usize {
+    part_2().count(grid)
+}
+
+/// The cells taking part in any [`part_1`] match, for [`mask::masked`].
+pub fn cells_ex1(grid: &Grid<char>) -> BTreeSet<Position> {
+    mask::cells(&part_1().find(grid))
+}
+
+/// The cells taking part in any [`part_2`] match, for [`mask::masked`].
+pub fn cells_ex2(grid: &Grid<char>) -> BTreeSet<Position> {
+    mask::cells(&part_2().find(grid))
+}
@@ -25,155 +25,35 @@
 
 // Take a look at the little Elf's word search. How many times does XMAS appear?
 
-use std::fmt::Display;
-
-const INPUT: &str = r#"
-MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX
-"#;
-
-const DIRECTIONS: [(isize, isize); 8] = [
-    (0, 1),
-    (1, 0),
-    (0, -1),
-    (-1, 0),
-    (1, 1),
-    (-1, -1),
-    (1, -1),
-    (-1, 1),
-];
-
-#[derive(Debug)]
-struct Grid<T> {
-    data: Vec<Vec<T>>,
-    width: usize,
-    height: usize,
-    default: T,
-}
-
-impl<T: Clone + Display> Grid<T> {
-    fn new(data: Vec<Vec<T>>, default: T) -> Self {
-        let height = data.len();
-        let width = if height > 0 { data[0].len() } else { 0 };
-        Self {
-            data,
-            width,
-            height,
-            default,
+use aoc_2024_4::grid::input_to_grid;
+use aoc_2024_4::{solve_ex1, solve_ex2};
+use aoc_2024_lib::cli::Args;
+
+fn main() -> std::io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(4);
+
+    for input in args.load("input.txt")? {
+        let grid = input_to_grid(&input.content, '.');
+        if args.runs_part(1) {
+            let record = reporter.time(1, &input.name, || solve_ex1(&grid));
+            reporter.emit(&record, |r| r.answer.clone());
         }
-    }
-
-    fn get(&self, row: isize, col: isize) -> &T {
-        if row < 0 || col < 0 || row >= self.height as isize || col >= self.width as isize {
-            &self.default
-        } else {
-            &self.data[row as usize][col as usize]
+        if args.runs_part(2) {
+            let record = reporter.time(2, &input.name, || solve_ex2(&grid));
+            reporter.emit(&record, |r| r.answer.clone());
         }
     }
 
-    fn cells(&self) -> impl Iterator<Item = (isize, isize, &T)> {
-        (0..self.height).flat_map(move |row| {
-            (0..self.width).map(move |col| (row as isize, col as isize, &self.data[row][col]))
-        })
-    }
-}
-
-impl<T: Clone + Display> Display for Grid<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut result = String::new();
-        for row in 0..self.height {
-            for col in 0..self.width {
-                result.push_str(&self.data[row][col].to_string());
-            }
-            if row < self.height - 1 {
-                result.push('\n');
-            }
-        }
-        write!(f, "{}", result)
-    }
-}
-
-fn input_to_grid(input: &str, default: char) -> Grid<char> {
-    Grid::new(
-        input
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty())
-            .map(|line| line.chars().collect())
-            .collect(),
-        default,
-    )
-}
-
-fn solve_ex1(grid: &Grid<char>) -> usize {
-    let mut count = 0;
-
-    for (row, col, &val) in grid.cells() {
-        if val != 'X' {
-            continue;
-        }
-
-        for (dr, dc) in DIRECTIONS {
-            let mut r = row;
-            let mut c = col;
-            for letter in "MAS".chars() {
-                r += dr;
-                c += dc;
-                if grid.get(r, c) != &letter {
-                    break;
-                } else if letter == 'S' {
-                    count += 1;
-                }
-            }
-        }
-    }
-
-    count
-}
-
-fn solve_ex2(grid: Grid<char>) -> usize {
-    let diagonals = [[(1, 1), (-1, -1)], [(1, -1), (-1, 1)]];
-
-    grid.cells()
-        .filter(|(row, col, &val)| {
-            val == 'A'
-                && diagonals
-                    .iter()
-                    .map(|diagonal| {
-                        diagonal
-                            .iter()
-                            .map(|(dr, dc)| grid.get(row + dr, col + dc))
-                            .collect::<Vec<_>>()
-                    })
-                    .all(|diag| diag.contains(&&'M') && diag.contains(&&'S'))
-        })
-        .count()
-}
-
-fn main() {
-    let grid = input_to_grid(INPUT, '.');
-
-    println!("{}", solve_ex1(&grid));
-    println!("{}", solve_ex2(grid));
-
-    let input = std::fs::read_to_string("input.txt").unwrap();
-    let grid = input_to_grid(&input, '.');
-    println!("{}", solve_ex1(&grid));
-    println!("{}", solve_ex2(grid));
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    static EXAMPLE: &str = include_str!("../example.txt");
+
     #[test]
     fn test_trivial_1() {
         let grid = input_to_grid("XMAS", '.');
@@ -222,7 +102,13 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let grid = input_to_grid(INPUT, '.');
+        let grid = input_to_grid(EXAMPLE, '.');
         assert_eq!(solve_ex1(&grid), 18);
     }
+
+    #[test]
+    fn test_example_2() {
+        let grid = input_to_grid(EXAMPLE, '.');
+        assert_eq!(solve_ex2(&grid), 9);
+    }
 }
@@ -0,0 +1,119 @@
+//! Views of the grid showing which cells take part in the matches, as in
+//! the puzzle statement.
+
+use crate::grid::Grid;
+use crate::search::{Match, Position};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Stands in for letters that are not part of any match.
+pub const BLANK: char = '.';
+
+/// Every cell that is part of at least one match.
+pub fn cells(matches: &[Match]) -> BTreeSet<Position> {
+    matches
+        .iter()
+        .flat_map(|found| found.cells.iter().copied())
+        .collect()
+}
+
+/// The grid with the letters outside `cells` blanked out.
+pub fn masked(grid: &Grid<char>, cells: &BTreeSet<Position>) -> Grid<char> {
+    grid.map(BLANK, |row, col, &letter| {
+        if cells.contains(&(row, col)) {
+            letter
+        } else {
+            BLANK
+        }
+    })
+}
+
+/// Number of matches going through a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Heat(pub usize);
+
+impl fmt::Display for Heat {
+    /// `.` for none, the digit up to 9 and `+` for more.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "{BLANK}"),
+            count @ 1..=9 => write!(f, "{count}"),
+            _ => write!(f, "+"),
+        }
+    }
+}
+
+/// For every cell, the number of matches it is part of.
+pub fn heatmap(grid: &Grid<char>, matches: &[Match]) -> Grid<Heat> {
+    let mut hits = HashMap::new();
+    for cell in matches.iter().flat_map(|found| &found.cells) {
+        *hits.entry(*cell).or_insert(0) += 1;
+    }
+    grid.map(Heat::default(), |row, col, _| {
+        Heat(hits.get(&(row, col)).copied().unwrap_or_default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::input_to_grid;
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn test_masked_part_1() {
+        let grid = input_to_grid(EXAMPLE, '.');
+        let cells = crate::cells_ex1(&grid);
+        let expected = "\
+....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX";
+        assert_eq!(masked(&grid, &cells).to_string(), expected);
+    }
+
+    #[test]
+    fn test_masked_part_2() {
+        let grid = input_to_grid(EXAMPLE, '.');
+        let cells = crate::cells_ex2(&grid);
+        let expected = "\
+.M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+..........";
+        assert_eq!(masked(&grid, &cells).to_string(), expected);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let grid = input_to_grid(
+            r#"
+                XMASAMX
+                M......
+                A......
+                S......
+            "#,
+            '.',
+        );
+        let matches = crate::part_1().find(&grid);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(
+            heatmap(&grid, &matches).to_string(),
+            "2112111\n1......\n1......\n1......"
+        );
+        assert_eq!(Heat(12).to_string(), "+");
+    }
+}
@@ -0,0 +1,420 @@
+//! Word search for any number of patterns at once.
+//!
+//! A pattern is either a word, read in any of the 8 directions, or a 2D
+//! block such as the `X-MAS` cross, tried in its 4 rotations and their
+//! mirror images. `.` in a block is a wildcard that matches anything, even
+//! outside the grid. Literal words are found with a single
+//! [`Dictionary`] scan, everything else is tried at every cell.
+
+use crate::dictionary::Dictionary;
+use crate::grid::Grid;
+use std::collections::HashSet;
+use std::fmt;
+
+/// `(row, col)`, as in [`Grid::cells`].
+pub type Position = (isize, isize);
+
+pub const DIRECTIONS: [Position; 8] = [
+    (0, 1),
+    (1, 0),
+    (0, -1),
+    (-1, 0),
+    (1, 1),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+];
+
+/// How a pattern is laid onto the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Orientation {
+    /// Grid step for the next column of the pattern.
+    pub right: Position,
+    /// Grid step for the next row of the pattern.
+    pub down: Position,
+}
+
+impl Orientation {
+    /// The reading directions of a word.
+    pub fn directions() -> impl Iterator<Item = Orientation> {
+        DIRECTIONS.into_iter().map(|right| Orientation {
+            right,
+            down: clockwise(right),
+        })
+    }
+
+    /// The rotations of a block and their mirror images.
+    pub fn symmetries() -> impl Iterator<Item = Orientation> {
+        DIRECTIONS[..4].iter().flat_map(|&right| {
+            [clockwise(right), counter_clockwise(right)].map(|down| Orientation { right, down })
+        })
+    }
+
+    pub fn is_mirrored(&self) -> bool {
+        self.down != clockwise(self.right)
+    }
+
+    /// Where the pattern cell `(row, col)` ends up with the pattern's `(0, 0)`
+    /// at `anchor`.
+    pub fn place(&self, anchor: Position, (row, col): Position) -> Position {
+        (
+            anchor.0 + row * self.down.0 + col * self.right.0,
+            anchor.1 + row * self.down.1 + col * self.right.1,
+        )
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let compass = match self.right {
+            (-1, 0) => "N",
+            (-1, 1) => "NE",
+            (0, 1) => "E",
+            (1, 1) => "SE",
+            (1, 0) => "S",
+            (1, -1) => "SW",
+            (0, -1) => "W",
+            (-1, -1) => "NW",
+            _ => "?",
+        };
+        write!(f, "{compass}")?;
+        if self.is_mirrored() {
+            write!(f, " mirrored")?;
+        }
+        Ok(())
+    }
+}
+
+fn clockwise((row, col): Position) -> Position {
+    (col, -row)
+}
+
+fn counter_clockwise((row, col): Position) -> Position {
+    (-col, row)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The letters to match, wildcards are left out.
+    cells: Vec<(Position, char)>,
+    rows: usize,
+}
+
+impl Pattern {
+    pub const WILDCARD: char = '.';
+
+    /// A word, taken literally.
+    pub fn word(word: &str) -> Self {
+        Self {
+            cells: word
+                .chars()
+                .enumerate()
+                .map(|(col, letter)| ((0, col as isize), letter))
+                .collect(),
+            rows: 1,
+        }
+    }
+
+    /// A block with [`Pattern::WILDCARD`]s. Lines are trimmed and empty lines
+    /// skipped, like [`crate::grid::input_to_grid`].
+    pub fn parse(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let cells = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, letter)| letter != Self::WILDCARD)
+                    .map(move |(col, letter)| ((row as isize, col as isize), letter))
+            })
+            .collect();
+        Self {
+            cells,
+            rows: lines.len(),
+        }
+    }
+
+    pub fn cells(&self) -> &[(Position, char)] {
+        &self.cells
+    }
+
+    /// The pattern as a plain word, if it is one row without wildcards.
+    pub fn as_word(&self) -> Option<String> {
+        let contiguous = self
+            .cells
+            .iter()
+            .enumerate()
+            .all(|(index, &(position, _))| position == (0, index as isize));
+        (self.rows == 1 && contiguous && !self.cells.is_empty())
+            .then(|| self.cells.iter().map(|&(_, letter)| letter).collect())
+    }
+
+    pub fn orientations(&self) -> Vec<Orientation> {
+        if self.rows <= 1 {
+            Orientation::directions().collect()
+        } else {
+            Orientation::symmetries().collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern in the [`Search`].
+    pub pattern: usize,
+    /// Where the pattern's `(0, 0)` is, which may be outside the grid for
+    /// blocks starting with a wildcard.
+    pub anchor: Position,
+    pub orientation: Orientation,
+    /// The matched letters, in the pattern's order.
+    pub cells: Vec<Position>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overlaps {
+    /// Every occurrence counts, as in the puzzle.
+    #[default]
+    Allow,
+    /// Occurrences may not share a cell. Earlier ones win, in reading order
+    /// of their anchors and then in pattern order.
+    Disallow,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    patterns: Vec<Pattern>,
+    overlaps: Overlaps,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pattern(mut self, pattern: Pattern) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    pub fn with_words(mut self, words: &[&str]) -> Self {
+        self.patterns
+            .extend(words.iter().map(|word| Pattern::word(word)));
+        self
+    }
+
+    pub fn with_overlaps(mut self, overlaps: Overlaps) -> Self {
+        self.overlaps = overlaps;
+        self
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    /// All occurrences, in reading order of their anchors. A pattern that
+    /// looks the same in several orientations, such as a palindrome, is only
+    /// found once per set of cells.
+    pub fn find(&self, grid: &Grid<char>) -> Vec<Match> {
+        let (words, blocks): (Vec<_>, Vec<_>) = self
+            .patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| (index, pattern.as_word()))
+            .partition(|(_, word)| word.is_some());
+
+        let dictionary = Dictionary::new(words.iter().filter_map(|(_, word)| word.as_deref()));
+        let mut matches = dictionary
+            .scan(grid)
+            .into_iter()
+            .map(|found| Match {
+                pattern: words[found.pattern].0,
+                ..found
+            })
+            .collect::<Vec<_>>();
+        for (index, _) in blocks {
+            matches.extend(self.find_pattern(grid, index));
+        }
+
+        self.select(matches)
+    }
+
+    pub fn count(&self, grid: &Grid<char>) -> usize {
+        self.find(grid).len()
+    }
+
+    fn find_pattern(&self, grid: &Grid<char>, index: usize) -> Vec<Match> {
+        let pattern = &self.patterns[index];
+        let Some(&(first, _)) = pattern.cells.first() else {
+            return Vec::new();
+        };
+        let orientations = pattern.orientations();
+
+        let mut matches = Vec::new();
+        for (row, col, _) in grid.cells() {
+            for &orientation in &orientations {
+                // Anchor the first letter on this cell, so each placement is
+                // tried exactly once.
+                let offset = orientation.place((0, 0), first);
+                let anchor = (row - offset.0, col - offset.1);
+                let cells = pattern
+                    .cells
+                    .iter()
+                    .map(|&(cell, letter)| {
+                        let (r, c) = orientation.place(anchor, cell);
+                        (grid.contains(r, c) && *grid.get(r, c) == letter).then_some((r, c))
+                    })
+                    .collect::<Option<Vec<_>>>();
+                if let Some(cells) = cells {
+                    matches.push(Match {
+                        pattern: index,
+                        anchor,
+                        orientation,
+                        cells,
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    fn select(&self, mut matches: Vec<Match>) -> Vec<Match> {
+        matches.sort_by_key(|found| (found.anchor, found.pattern, found.orientation));
+
+        let mut seen = HashSet::new();
+        let mut used = HashSet::new();
+        matches.retain(|found| {
+            let mut cells = found.cells.clone();
+            cells.sort_unstable();
+            if !seen.insert((found.pattern, cells)) {
+                return false;
+            }
+            match self.overlaps {
+                Overlaps::Allow => true,
+                Overlaps::Disallow => {
+                    let free = found.cells.iter().all(|cell| !used.contains(cell));
+                    if free {
+                        used.extend(found.cells.iter().copied());
+                    }
+                    free
+                }
+            }
+        });
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::input_to_grid;
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn test_orientations() {
+        let symmetries = Orientation::symmetries().collect::<HashSet<_>>();
+        assert_eq!(symmetries.len(), 8);
+        let mirrored = symmetries.iter().filter(|o| o.is_mirrored()).count();
+        assert_eq!(mirrored, 4);
+        assert!(Orientation::directions().all(|o| !o.is_mirrored()));
+
+        let east = Orientation::directions().next().unwrap();
+        assert_eq!(east.to_string(), "E");
+        assert_eq!(east.place((2, 3), (1, 2)), (3, 5));
+    }
+
+    #[test]
+    fn test_pattern_parse() {
+        let pattern = Pattern::parse("M.S\n.A.\nM.S");
+        assert_eq!(
+            pattern.cells(),
+            &[
+                ((0, 0), 'M'),
+                ((0, 2), 'S'),
+                ((1, 1), 'A'),
+                ((2, 0), 'M'),
+                ((2, 2), 'S')
+            ]
+        );
+        assert_eq!(pattern.as_word(), None);
+        assert_eq!(Pattern::parse("X.AS").as_word(), None);
+        assert_eq!(Pattern::parse("XMAS").as_word(), Some("XMAS".to_string()));
+        assert_eq!(Pattern::word("X.AS").as_word(), Some("X.AS".to_string()));
+    }
+
+    #[test]
+    fn test_find_positions() {
+        let grid = input_to_grid("..X\n.M.\nA..\n", '.');
+        let matches = Search::new().with_words(&["XMA"]).find(&grid);
+        assert_eq!(
+            matches,
+            vec![Match {
+                pattern: 0,
+                anchor: (0, 2),
+                orientation: Orientation {
+                    right: (1, -1),
+                    down: (-1, -1)
+                },
+                cells: vec![(0, 2), (1, 1), (2, 0)],
+            }]
+        );
+        assert_eq!(matches[0].orientation.to_string(), "SW");
+    }
+
+    #[test]
+    fn test_dictionary_matches_brute_force() {
+        let grid = input_to_grid(EXAMPLE, '.');
+        let words = ["XMAS", "MAS", "AM", "SAMX", "M", "XX"];
+        let search = Search::new().with_words(&words);
+
+        let mut brute = (0..words.len())
+            .flat_map(|index| search.find_pattern(&grid, index))
+            .collect::<Vec<_>>();
+        brute = search.select(brute);
+        assert_eq!(search.find(&grid), brute);
+    }
+
+    #[test]
+    fn test_multiple_patterns() {
+        let grid = input_to_grid(EXAMPLE, '.');
+        let search = Search::new()
+            .with_words(&["XMAS"])
+            .with_pattern(Pattern::parse("M.S\n.A.\nM.S"));
+        let matches = search.find(&grid);
+        assert_eq!(matches.iter().filter(|m| m.pattern == 0).count(), 18);
+        assert_eq!(matches.iter().filter(|m| m.pattern == 1).count(), 9);
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let grid = input_to_grid("XMAS\nXZAS", '.');
+        let search = Search::new().with_pattern(Pattern::parse("X.AS"));
+        assert_eq!(search.count(&grid), 2);
+        // Wildcards may hang over the edge.
+        let search = Search::new().with_pattern(Pattern::parse(".X\n.."));
+        assert_eq!(search.count(&grid), 2);
+    }
+
+    #[test]
+    fn test_palindromes_once() {
+        let grid = input_to_grid("ABA", '.');
+        assert_eq!(Search::new().with_words(&["ABA"]).count(&grid), 1);
+        assert_eq!(Search::new().with_words(&["A"]).count(&grid), 2);
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let grid = input_to_grid("XMASAMX", '.');
+        let search = Search::new().with_words(&["XMAS"]);
+        assert_eq!(search.count(&grid), 2);
+        let search = search.with_overlaps(Overlaps::Disallow);
+        let matches = search.find(&grid);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].anchor, (0, 0));
+    }
+}
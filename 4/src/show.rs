@@ -0,0 +1,24 @@
+//! Prints the grid with the letters outside any match blanked out, then how
+//! many matches go through each cell: `cargo run --bin show -- --part 2`.
+
+use aoc_2024_4::grid::input_to_grid;
+use aoc_2024_4::mask::{cells, heatmap, masked};
+use aoc_2024_4::{part_1, part_2};
+use aoc_2024_lib::cli::Args;
+
+fn main() -> std::io::Result<()> {
+    let args = Args::from_env();
+    let searches = [(1, part_1()), (2, part_2())];
+
+    for input in args.load("input.txt")? {
+        let grid = input_to_grid(&input.content, '.');
+        for (part, search) in searches.iter().filter(|(part, _)| args.runs_part(*part)) {
+            let matches = search.find(&grid);
+            println!("{} part {part}: {} matches\n", input.name, matches.len());
+            println!("{}\n", masked(&grid, &cells(&matches)));
+            println!("{}\n", heatmap(&grid, &matches));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Prints how each calibration line can be solved and which ones only work
+//! with `||`: `cargo run --bin audit -- --input example.txt`.
+
+use aoc_2024_7::operator::Operators;
+use aoc_2024_7::Equation;
+use aoc_2024_lib::cli::Args;
+
+fn main() -> std::io::Result<()> {
+    let args = Args::from_env();
+    let without_concatenation = Operators::part_1();
+    let all = Operators::part_2();
+
+    for input in args.load("./input.txt")? {
+        let (mut plain, mut concatenated, mut unsolvable, mut invalid) = (0, 0, 0, 0);
+        for (index, line) in input.content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let equation = match line.parse::<Equation>() {
+                Ok(equation) => equation,
+                Err(reason) => {
+                    invalid += 1;
+                    println!("{:>4}  {:<10}  {line}  ({reason})", index + 1, "invalid");
+                    continue;
+                }
+            };
+            let status = if equation.is_solvable(&without_concatenation).unwrap() {
+                plain += 1;
+                "ok"
+            } else if equation.is_solvable(&all).unwrap() {
+                concatenated += 1;
+                "needs ||"
+            } else {
+                unsolvable += 1;
+                "unsolvable"
+            };
+
+            let count = equation.count_solutions(&all).unwrap();
+            match equation.first_solution(&all).unwrap() {
+                Some(solution) => println!(
+                    "{:>4}  {status:<10}  {solution}  ({count} solution{})",
+                    index + 1,
+                    if count == 1 { "" } else { "s" }
+                ),
+                None => println!("{:>4}  {status:<10}  {line}", index + 1),
+            }
+        }
+        println!(
+            "{}: {plain} solvable with + and *, {concatenated} more with ||, {unsolvable} unsolvable, {invalid} invalid",
+            input.name
+        );
+    }
+
+    Ok(())
+}
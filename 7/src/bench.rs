@@ -0,0 +1,74 @@
+//! Times both solver strategies on generated equations with many operands:
+//! `bench [OPERANDS] [EQUATIONS] [SEED]`.
+//!
+//! Numbers are kept between 1 and 3, so most operators can be undone at most
+//! steps and backtracking has many branches to try, while the sets of
+//! distinct values stay small. Half of the targets are off by one, so that
+//! some equations have no solution.
+
+use aoc_2024_7::operator::Operators;
+use aoc_2024_7::{Equation, Strategy};
+use aoc_2024_lib::num::Num;
+use aoc_2024_lib::rng::Rng;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: bench [OPERANDS] [EQUATIONS] [SEED]";
+
+/// Keeps generated targets well inside 64 bits, even after a `||`.
+const LIMIT: i64 = 1_000_000_000_000;
+
+fn generate(operands: usize, count: u64, seed: u64) -> Vec<Equation> {
+    let mut rng = Rng::new(seed);
+    let mut next = |bound: u64| rng.below(bound);
+    (0..count)
+        .map(|i| {
+            let numbers = (0..operands)
+                .map(|_| 1 + next(3) as i64)
+                .collect::<Vec<_>>();
+            let mut target = numbers[0];
+            for &number in &numbers[1..] {
+                target = match next(2) {
+                    0 if target * number < LIMIT => target * number,
+                    _ => target + number,
+                };
+            }
+            Equation {
+                target: Num::from(target + (i % 2) as i64),
+                numbers: numbers.into_iter().map(Num::from).collect(),
+            }
+        })
+        .collect()
+}
+
+fn time(equations: &[Equation], operators: &Operators, strategy: Strategy) -> (usize, Duration) {
+    let start = Instant::now();
+    let solvable = equations
+        .iter()
+        .filter(|eq| eq.is_solvable_with(operators, strategy).unwrap())
+        .count();
+    (solvable, start.elapsed())
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut parse = |default: u64| args.next().map_or(Ok(default), |arg| arg.parse::<u64>());
+    let (Ok(operands @ 2..), Ok(count), Ok(seed)) = (parse(30), parse(20), parse(2024)) else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+
+    let equations = generate(operands as usize, count, seed);
+    println!("{count} equations with {operands} operands");
+    for (name, operators) in [
+        ("+ *", Operators::part_1()),
+        ("+ * ||", Operators::part_2()),
+    ] {
+        let mut results = Vec::new();
+        for strategy in [Strategy::Backtracking, Strategy::ReachableSets] {
+            let (solvable, duration) = time(&equations, &operators, strategy);
+            println!("{name:>8} {strategy:?}: {solvable} solvable, {duration:?}");
+            results.push(solvable);
+        }
+        assert_eq!(results[0], results[1], "strategies disagree");
+    }
+}
@@ -0,0 +1,821 @@
+use aoc_2024_lib::num::{Num, Overflow};
+use operator::{Evaluation, Operator, Operators};
+use std::fmt;
+use std::ops::ControlFlow;
+use std::str::FromStr;
+
+pub mod operator;
+mod reachable;
+
+#[derive(Debug, PartialEq)]
+pub struct Equation {
+    pub target: Num,
+    pub numbers: Vec<Num>,
+}
+
+impl FromStr for Equation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (target_str, numbers_str) = s.split_once(':').ok_or("missing colon")?;
+        if numbers_str.trim().is_empty() {
+            return Err("no numbers".to_string());
+        }
+
+        Ok(Equation {
+            target: target_str
+                .trim()
+                .parse()
+                .map_err(|e| format!("invalid target: {}", e))?,
+            numbers: numbers_str
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|e| format!("invalid number: {}", e)))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Operators between the numbers of an equation, left to right, that give
+/// its target. Displays as `190 = 10 * 19`.
+#[derive(Debug, Clone)]
+pub struct Solution<'a> {
+    pub equation: &'a Equation,
+    pub operators: Vec<&'a dyn Operator>,
+}
+
+impl fmt::Display for Solution<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.equation.target, self.equation.numbers[0])?;
+        for (operator, number) in self.operators.iter().zip(&self.equation.numbers[1..]) {
+            write!(f, " {operator} {number}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How [`Equation::is_solvable_with`] and [`Equation::count_solutions_with`]
+/// look for solutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Depth first over operator sequences, undoing operators from the target
+    /// with left-to-right evaluation. Stops at the first solution when one is
+    /// enough, but can visit exponentially many sequences.
+    #[default]
+    Backtracking,
+    /// Over the sets of distinct values reachable at each position, meeting
+    /// in the middle for left-to-right evaluation. Values are computed before
+    /// they are compared with the target, so they can overflow where
+    /// backtracking would not.
+    ReachableSets,
+}
+
+type Visit<'v, 'a> = dyn FnMut(&[&'a dyn Operator]) -> ControlFlow<()> + 'v;
+
+impl Equation {
+    pub fn is_solvable(&self, operators: &Operators) -> Result<bool, Overflow> {
+        Ok(self.first_solution(operators)?.is_some())
+    }
+
+    pub fn is_solvable_with(
+        &self,
+        operators: &Operators,
+        strategy: Strategy,
+    ) -> Result<bool, Overflow> {
+        match strategy {
+            Strategy::Backtracking => self.is_solvable(operators),
+            Strategy::ReachableSets => Ok(reachable::count_solutions(self, operators)? > 0),
+        }
+    }
+
+    pub fn first_solution<'a>(
+        &'a self,
+        operators: &'a Operators,
+    ) -> Result<Option<Solution<'a>>, Overflow> {
+        let mut first = None;
+        self.search(operators, &mut |found| {
+            first = Some(found.to_vec());
+            ControlFlow::Break(())
+        })?;
+        Ok(first.map(|operators| Solution {
+            equation: self,
+            operators,
+        }))
+    }
+
+    /// Every solution, in no particular order.
+    pub fn solutions<'a>(
+        &'a self,
+        operators: &'a Operators,
+    ) -> Result<Vec<Solution<'a>>, Overflow> {
+        let mut all = Vec::new();
+        self.search(operators, &mut |found| {
+            all.push(Solution {
+                equation: self,
+                operators: found.to_vec(),
+            });
+            ControlFlow::Continue(())
+        })?;
+        Ok(all)
+    }
+
+    /// Number of distinct operator sequences that solve the equation.
+    pub fn count_solutions(&self, operators: &Operators) -> Result<usize, Overflow> {
+        let mut count = 0;
+        self.search(operators, &mut |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        })?;
+        Ok(count)
+    }
+
+    pub fn count_solutions_with(
+        &self,
+        operators: &Operators,
+        strategy: Strategy,
+    ) -> Result<usize, Overflow> {
+        match strategy {
+            Strategy::Backtracking => self.count_solutions(operators),
+            Strategy::ReachableSets => reachable::count_solutions(self, operators),
+        }
+    }
+
+    /// The value of the numbers with `operators` in between, `None` if an
+    /// operator is not defined for its operands.
+    pub fn evaluate(
+        &self,
+        operators: &[&dyn Operator],
+        evaluation: Evaluation,
+    ) -> Result<Option<Num>, Overflow> {
+        let mut values = vec![self.numbers[0].clone()];
+        let mut pending = Vec::new();
+        for (&operator, number) in operators.iter().zip(&self.numbers[1..]) {
+            let precedence = match evaluation {
+                Evaluation::LeftToRight => 0,
+                Evaluation::Precedence => operator.precedence(),
+            };
+            if !reduce(&mut values, &mut pending, |operator| operator, precedence)? {
+                return Ok(None);
+            }
+            pending.push(operator);
+            values.push(number.clone());
+        }
+        if !reduce(&mut values, &mut pending, |operator| operator, 0)? {
+            return Ok(None);
+        }
+        Ok(values.pop())
+    }
+
+    /// Calls `visit` with the operators of each solution until it breaks.
+    fn search<'a>(
+        &self,
+        operators: &'a Operators,
+        visit: &mut Visit<'_, 'a>,
+    ) -> Result<(), Overflow> {
+        match operators.evaluation() {
+            Evaluation::LeftToRight => {
+                let non_negative = operators.keep_non_negative()
+                    && self.numbers.iter().all(|number| !number.is_negative());
+                let mut stack = Vec::with_capacity(self.numbers.len());
+                self.try_solve(
+                    self.numbers.len() - 1,
+                    (&self.target, &self.target),
+                    operators,
+                    non_negative,
+                    &mut stack,
+                    visit,
+                )
+            }
+            Evaluation::Precedence => {
+                let mut chosen = Vec::with_capacity(self.numbers.len());
+                self.try_forward(
+                    1,
+                    vec![self.numbers[0].clone()],
+                    Vec::new(),
+                    operators,
+                    &mut chosen,
+                    visit,
+                )
+            }
+        }
+        .map(|_| ())
+    }
+
+    /// Works back from the target, undoing the operators from the right. What
+    /// the numbers up to `pos` must give is kept as a range, as undoing a
+    /// division leaves one, and never listed value by value.
+    fn try_solve<'a>(
+        &self,
+        pos: usize,
+        (first, last): (&Num, &Num),
+        operators: &'a Operators,
+        non_negative: bool,
+        stack: &mut Vec<&'a dyn Operator>,
+        visit: &mut Visit<'_, 'a>,
+    ) -> Result<ControlFlow<()>, Overflow> {
+        if pos == 0 {
+            if *first <= self.numbers[0] && self.numbers[0] <= *last {
+                let ordered = stack.iter().rev().copied().collect::<Vec<_>>();
+                return Ok(visit(&ordered));
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        let next = &self.numbers[pos];
+        for op in operators.iter() {
+            for (prev_first, prev_last) in op.inverse(first, last, next)?.0 {
+                let prev_first = if non_negative && prev_first.is_negative() {
+                    Num::zero()
+                } else {
+                    prev_first
+                };
+                if prev_first > prev_last {
+                    continue;
+                }
+                stack.push(op);
+                let flow = self.try_solve(
+                    pos - 1,
+                    (&prev_first, &prev_last),
+                    operators,
+                    non_negative,
+                    stack,
+                    visit,
+                )?;
+                stack.pop();
+                if flow.is_break() {
+                    return Ok(flow);
+                }
+            }
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Tries every operator sequence from the left, evaluating by precedence
+    /// with a value and an operator stack. There is no inverse for a partial
+    /// expression, so nothing is pruned.
+    fn try_forward<'a>(
+        &self,
+        pos: usize,
+        values: Vec<Num>,
+        pending: Vec<&'a dyn Operator>,
+        operators: &'a Operators,
+        chosen: &mut Vec<&'a dyn Operator>,
+        visit: &mut Visit<'_, 'a>,
+    ) -> Result<ControlFlow<()>, Overflow> {
+        if pos == self.numbers.len() {
+            let (mut values, mut pending) = (values, pending);
+            if reduce(&mut values, &mut pending, |operator| operator, 0)?
+                && values.pop().as_ref() == Some(&self.target)
+            {
+                return Ok(visit(chosen));
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        for op in operators.iter() {
+            let (mut values, mut pending) = (values.clone(), pending.clone());
+            if !reduce(
+                &mut values,
+                &mut pending,
+                |operator| operator,
+                op.precedence(),
+            )? {
+                continue;
+            }
+            pending.push(op);
+            values.push(self.numbers[pos].clone());
+
+            chosen.push(op);
+            let flow = self.try_forward(pos + 1, values, pending, operators, chosen, visit)?;
+            chosen.pop();
+            if flow.is_break() {
+                return Ok(flow);
+            }
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+/// Applies the pending operators binding at least as tightly as
+/// `precedence`, `false` if one of them is undefined for its operands.
+/// `pending` holds anything `operator` maps to an operator.
+fn reduce<'o, P: Copy>(
+    values: &mut Vec<Num>,
+    pending: &mut Vec<P>,
+    operator: impl Fn(P) -> &'o dyn Operator,
+    precedence: u8,
+) -> Result<bool, Overflow> {
+    while let Some(&last) = pending.last() {
+        let operator = operator(last);
+        if operator.precedence() < precedence {
+            break;
+        }
+        pending.pop();
+        let rhs = values.pop().expect("one value per operator");
+        let lhs = values.pop().expect("one value per operator");
+        match operator.apply(&lhs, &rhs)? {
+            Some(value) => values.push(value),
+            None => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+/// A line of the input that is not an equation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based.
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} in {:?}", self.line, self.reason, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    Overflow(Overflow),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => error.fmt(f),
+            Error::Overflow(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(error) => Some(error),
+            Error::Overflow(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl From<Overflow> for Error {
+    fn from(error: Overflow) -> Self {
+        Error::Overflow(error)
+    }
+}
+
+/// What to do with lines that are not equations. Blank lines are always
+/// skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parsing {
+    /// Fail on the first one, before solving anything.
+    #[default]
+    Strict,
+    /// Leave them out of the sum and report them.
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration {
+    /// Sum of the targets of the solvable equations.
+    pub total: Num,
+    /// The lines left out with [`Parsing::Lenient`], in input order.
+    pub skipped: Vec<ParseError>,
+}
+
+/// Sum of the targets of the solvable equations, failing on any line that is
+/// not an equation.
+pub fn sum_up_solvable_equations(input: &str, operators: &Operators) -> Result<Num, Error> {
+    sum_up_solvable_equations_with(input, operators, Strategy::default(), Parsing::Strict)
+        .map(|calibration| calibration.total)
+}
+
+pub fn sum_up_solvable_equations_with(
+    input: &str,
+    operators: &Operators,
+    strategy: Strategy,
+    parsing: Parsing,
+) -> Result<Calibration, Error> {
+    let mut equations = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.parse::<Equation>() {
+            Ok(equation) => equations.push(equation),
+            Err(reason) => {
+                let error = ParseError {
+                    line: index + 1,
+                    text: line.to_string(),
+                    reason,
+                };
+                match parsing {
+                    Parsing::Strict => return Err(error.into()),
+                    Parsing::Lenient => skipped.push(error),
+                }
+            }
+        }
+    }
+
+    let mut total = Num::zero();
+    for equation in &equations {
+        if equation.is_solvable_with(operators, strategy)? {
+            total = total.add(&equation.target)?;
+        }
+    }
+    Ok(Calibration { total, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use operator::{Add, Concatenate, Divide, Multiply, Subtract, Xor};
+    use std::fs::read_to_string;
+
+    fn num(value: i64) -> Num {
+        Num::from(value)
+    }
+
+    #[test]
+    fn test_example() {
+        let input = read_to_string("./example.txt").unwrap();
+        assert_eq!(
+            sum_up_solvable_equations(&input, &Operators::part_1()),
+            Ok(num(3749))
+        );
+    }
+
+    #[test]
+    fn test_simple_cases() {
+        // Only multiplication works
+        assert_eq!(
+            sum_up_solvable_equations("190: 10 19\n", &Operators::new().with(Multiply)),
+            Ok(num(190))
+        );
+        // Only addition works
+        assert_eq!(
+            sum_up_solvable_equations("20: 10 10\n", &Operators::new().with(Add)),
+            Ok(num(20))
+        );
+        // No solution possible
+        assert_eq!(
+            sum_up_solvable_equations("30: 10 10\n", &Operators::new().with(Add)),
+            Ok(num(0))
+        );
+        // (10 + 20) * 2 = 60 works
+        assert_eq!(
+            sum_up_solvable_equations("60: 10 20 2\n", &Operators::part_1()),
+            Ok(num(60))
+        );
+    }
+
+    #[test]
+    fn test_parse_equation() {
+        let eq: Equation = "190: 10 19".parse().unwrap();
+        assert_eq!(eq.target, num(190));
+        assert_eq!(eq.numbers, vec![num(10), num(19)]);
+    }
+
+    #[test]
+    fn test_operations() {
+        let eq: Equation = "190: 10 19".parse().unwrap();
+        assert_eq!(eq.is_solvable(&Operators::part_1()), Ok(true));
+
+        let eq: Equation = "29: 10 19".parse().unwrap();
+        assert_eq!(eq.is_solvable(&Operators::part_1()), Ok(true));
+
+        let eq: Equation = "1019: 10 19".parse().unwrap();
+        assert_eq!(eq.is_solvable(&Operators::part_2()), Ok(true));
+    }
+
+    #[test]
+    fn test_concatenation_cases() {
+        assert_eq!(
+            sum_up_solvable_equations("156: 15 6\n", &Operators::new().with(Concatenate::DECIMAL)),
+            Ok(num(156))
+        );
+        assert_eq!(
+            sum_up_solvable_equations("7290: 6 8 6 15\n", &Operators::part_2()),
+            Ok(num(7290))
+        );
+        assert_eq!(
+            sum_up_solvable_equations("192: 17 8 14\n", &Operators::part_2()),
+            Ok(num(192))
+        );
+    }
+
+    #[test]
+    fn test_first_solution() {
+        let operators = Operators::part_2();
+        let eq: Equation = "190: 10 19".parse().unwrap();
+        let solution = eq.first_solution(&operators).unwrap().unwrap();
+        assert_eq!(solution.operators.len(), 1);
+        assert_eq!(solution.to_string(), "190 = 10 * 19");
+
+        let eq: Equation = "7290: 6 8 6 15".parse().unwrap();
+        assert_eq!(
+            eq.first_solution(&operators).unwrap().unwrap().to_string(),
+            "7290 = 6 * 8 || 6 * 15"
+        );
+        assert!(eq.first_solution(&Operators::part_1()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_all_solutions() {
+        let operators = Operators::part_2();
+        let eq: Equation = "3267: 81 40 27".parse().unwrap();
+        let mut solutions = eq
+            .solutions(&operators)
+            .unwrap()
+            .iter()
+            .map(|solution| solution.to_string())
+            .collect::<Vec<_>>();
+        solutions.sort();
+        assert_eq!(
+            solutions,
+            vec!["3267 = 81 * 40 + 27", "3267 = 81 + 40 * 27"]
+        );
+        assert_eq!(eq.count_solutions(&operators), Ok(2));
+
+        // 2 + 2 = 2 * 2, and 1 * 1 = 1 * 1 for any number of ones.
+        let eq: Equation = "4: 2 2".parse().unwrap();
+        assert_eq!(eq.count_solutions(&operators), Ok(2));
+        let eq: Equation = "1: 1 1 1 1".parse().unwrap();
+        assert_eq!(eq.count_solutions(&operators), Ok(1));
+        let eq: Equation = "83: 17 5".parse().unwrap();
+        assert_eq!(eq.count_solutions(&operators), Ok(0));
+        assert!(eq.solutions(&operators).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_solutions_are_valid() {
+        let input = read_to_string("./example.txt").unwrap();
+        let operators = Operators::part_2();
+        for eq in input.lines().map(|line| line.parse::<Equation>().unwrap()) {
+            for solution in eq.solutions(&operators).unwrap() {
+                assert_eq!(
+                    eq.evaluate(&solution.operators, Evaluation::LeftToRight),
+                    Ok(Some(eq.target.clone())),
+                    "{solution}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_operators() {
+        let solve = |line: &str, operators: &Operators| {
+            let eq: Equation = line.parse().unwrap();
+            let solution = eq.first_solution(operators).unwrap();
+            solution.map(|solution| solution.to_string())
+        };
+        let with = |operator| Operators::part_1().with(operator);
+
+        assert_eq!(
+            solve("5: 10 5", &Operators::new().with(Subtract)),
+            Some("5 = 10 - 5".to_string())
+        );
+        assert_eq!(
+            solve("3: 7 2", &Operators::new().with(Divide)),
+            Some("3 = 7 / 2".to_string())
+        );
+        assert_eq!(
+            solve("5: 6 3", &Operators::new().with(Xor)),
+            Some("5 = 6 ^ 3".to_string())
+        );
+        assert_eq!(
+            solve("47: 5 7", &Operators::new().with(Concatenate::new(2))),
+            Some("47 = 5 ||2 7".to_string())
+        );
+        // Needs a negative intermediate value.
+        assert_eq!(
+            solve("5: 1 3 7", &with(Subtract)),
+            Some("5 = 1 - 3 + 7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_precedence() {
+        let operators = Operators::part_2().with_evaluation(Evaluation::Precedence);
+        let eq: Equation = "3267: 81 40 27".parse().unwrap();
+        let solutions = eq.solutions(&operators).unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].to_string(), "3267 = 81 * 40 + 27");
+
+        // (11 + 6) * 16 + 20 only works from left to right.
+        let eq: Equation = "292: 11 6 16 20".parse().unwrap();
+        assert_eq!(eq.is_solvable(&Operators::part_2()), Ok(true));
+        assert_eq!(eq.is_solvable(&operators), Ok(false));
+        // 2 + 3 * 4 only works with precedence.
+        let eq: Equation = "14: 2 3 4".parse().unwrap();
+        assert_eq!(eq.is_solvable(&Operators::part_2()), Ok(false));
+        assert_eq!(
+            eq.first_solution(&operators).unwrap().unwrap().to_string(),
+            "14 = 2 + 3 * 4"
+        );
+        assert_eq!(
+            eq.evaluate(&[&Add, &Multiply], Evaluation::Precedence),
+            Ok(Some(num(14)))
+        );
+        assert_eq!(
+            eq.evaluate(&[&Add, &Multiply], Evaluation::LeftToRight),
+            Ok(Some(num(20)))
+        );
+    }
+
+    /// Number of operator sequences that solve `eq`, evaluating every one
+    /// from the left.
+    fn brute_force(eq: &Equation, operators: &Operators) -> usize {
+        let all = operators.iter().collect::<Vec<_>>();
+        let mut sequences = vec![Vec::new()];
+        for _ in 1..eq.numbers.len() {
+            sequences = sequences
+                .into_iter()
+                .flat_map(|sequence: Vec<&dyn Operator>| {
+                    all.iter().map(move |&operator| {
+                        let mut sequence = sequence.clone();
+                        sequence.push(operator);
+                        sequence
+                    })
+                })
+                .collect();
+        }
+        sequences
+            .iter()
+            .filter(|sequence| {
+                eq.evaluate(sequence, Evaluation::LeftToRight) == Ok(Some(eq.target.clone()))
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_reverse_search_matches_brute_force() {
+        let operators = Operators::part_2()
+            .with(Subtract)
+            .with(Divide)
+            .with(Xor)
+            .with(Concatenate::new(2));
+        let mut seed = 7_u64;
+        for _ in 0..200 {
+            let mut next = |bound: u64| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (seed >> 33) % bound
+            };
+            let count = 2 + next(3) as usize;
+            let numbers = (0..count)
+                .map(|_| num(1 + next(12) as i64))
+                .collect::<Vec<_>>();
+            let target = num(next(60) as i64 - 10);
+            let eq = Equation { target, numbers };
+            assert_eq!(
+                eq.count_solutions(&operators),
+                Ok(brute_force(&eq, &operators)),
+                "{eq:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_huge_divisors() {
+        // Each division leaves a range 10^12 wide, which the search would never
+        // get through value by value.
+        let operators = Operators::part_1().with(Divide).with(Xor);
+        for line in [
+            "1: 1 1000000000000 1000000000000",
+            "0: 1 1000000000000 1000000000000",
+            "3: 7000000000000 2 1000000000000 1000000000000",
+            "1: 3000000000005 1000000000000 3 1",
+        ] {
+            let eq: Equation = line.parse().unwrap();
+            let expected = brute_force(&eq, &operators);
+            for strategy in [Strategy::Backtracking, Strategy::ReachableSets] {
+                assert_eq!(
+                    eq.count_solutions_with(&operators, strategy),
+                    Ok(expected),
+                    "{line} with {strategy:?}"
+                );
+            }
+        }
+        let eq: Equation = "1: 1000000000000000000000000 1000000000000 1000000000000"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            eq.first_solution(&Operators::new().with(Divide))
+                .unwrap()
+                .map(|solution| solution.to_string()),
+            Some("1 = 1000000000000000000000000 / 1000000000000 / 1000000000000".to_string())
+        );
+    }
+
+    const MIXED: &str = "190: 10 19\n\nnot an equation\n83: 17 5\n29 10 19\n292: \n3267: 81 40 x\n";
+
+    #[test]
+    fn test_strict_parsing() {
+        let error = sum_up_solvable_equations(MIXED, &Operators::part_1()).unwrap_err();
+        assert_eq!(
+            error,
+            Error::Parse(ParseError {
+                line: 3,
+                text: "not an equation".to_string(),
+                reason: "missing colon".to_string(),
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 3: missing colon in \"not an equation\""
+        );
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        let calibration = sum_up_solvable_equations_with(
+            MIXED,
+            &Operators::part_1(),
+            Strategy::default(),
+            Parsing::Lenient,
+        )
+        .unwrap();
+        assert_eq!(calibration.total, num(190));
+        let skipped = calibration
+            .skipped
+            .iter()
+            .map(|error| (error.line, error.reason.split(':').next().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (3, "missing colon"),
+                (5, "missing colon"),
+                (6, "no numbers"),
+                (7, "invalid number"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_target_beyond_64_bits() {
+        // Beyond i64, but still a u64 as the targets were originally parsed.
+        let input = "190: 10 19\n18000000000000000000: 9000000000000000000 9000000000000000000\n";
+        assert_eq!(
+            sum_up_solvable_equations(input, &Operators::new().with(Add))
+                .map(|sum| sum.to_string()),
+            Ok("18000000000000000000".to_string())
+        );
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_target_beyond_128_bits_is_reported() {
+        let input = "190: 10 19\n999999999999999999999999999999999999999: 1 2\n";
+        let Err(Error::Parse(error)) = sum_up_solvable_equations(input, &Operators::part_1())
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.line, 2);
+        assert!(error.reason.starts_with("invalid target"), "{error}");
+    }
+
+    #[cfg(feature = "checked")]
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_sum_overflow_is_reported() {
+        let input =
+            "100000000000000000000000000000000000000: 100000000000000000000000000000000000000
+100000000000000000000000000000000000000: 100000000000000000000000000000000000000
+";
+        let Err(Error::Overflow(err)) =
+            sum_up_solvable_equations(input, &Operators::new().with(Add))
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!(err.op, "+");
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_sum_beyond_128_bits() {
+        let input =
+            "100000000000000000000000000000000000000: 100000000000000000000000000000000000000
+100000000000000000000000000000000000000: 100000000000000000000000000000000000000
+";
+        assert_eq!(
+            sum_up_solvable_equations(input, &Operators::new().with(Add))
+                .map(|sum| sum.to_string()),
+            Ok("200000000000000000000000000000000000000".to_string())
+        );
+    }
+}
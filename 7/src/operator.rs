@@ -0,0 +1,590 @@
+//! Operators that can be placed between the numbers of an equation.
+//!
+//! Each [`Operator`] defines how to apply it and how to undo it, the latter
+//! being what the reverse search from the target uses. New operators only
+//! need an implementation of the trait and a place in [`Operators`].
+
+use aoc_2024_lib::num::{Num, Overflow};
+use std::fmt;
+
+pub trait Operator: fmt::Debug + fmt::Display {
+    /// `lhs op rhs`, or `None` where the operator is not defined.
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow>;
+
+    /// Every `lhs` with `lhs op rhs` from `first` to `last`, both included.
+    /// Operators with infinitely many such values, like `* 0`, return none.
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow>;
+
+    /// How tightly the operator binds with [`Evaluation::Precedence`],
+    /// higher goes first.
+    fn precedence(&self) -> u8;
+
+    /// Whether non-negative operands always give a non-negative result. If
+    /// all operators do, the reverse search drops negative intermediate
+    /// values.
+    fn keeps_non_negative(&self) -> bool;
+
+    /// Whether `lhs op rhs >= lhs` for every `lhs >= 0` and `rhs >= 1`. If
+    /// all operators are, [`crate::Strategy::ReachableSets`] drops values
+    /// above the target.
+    fn is_monotonic(&self) -> bool;
+}
+
+/// The values an [`Operator::inverse`] finds, as ranges that include both
+/// ends. They are never listed one by one, so a range costs the same however
+/// wide it is.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Inverse(pub Vec<(Num, Num)>);
+
+impl Inverse {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// None if `first` is above `last`.
+    pub fn range(first: Num, last: Num) -> Self {
+        if first <= last {
+            Self(vec![(first, last)])
+        } else {
+            Self::none()
+        }
+    }
+
+    pub fn contains(&self, value: &Num) -> bool {
+        self.0
+            .iter()
+            .any(|(first, last)| first <= value && value <= last)
+    }
+}
+
+/// `lhs / rhs` rounded down.
+fn div_floor(lhs: &Num, rhs: &Num) -> Result<Num, Overflow> {
+    let (quotient, rest) = (lhs.div(rhs)?, lhs.rem(rhs)?);
+    if !rest.is_zero() && rest.is_negative() != rhs.is_negative() {
+        quotient.sub(&Num::one())
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// `lhs / rhs` rounded up.
+fn div_ceil(lhs: &Num, rhs: &Num) -> Result<Num, Overflow> {
+    let (quotient, rest) = (lhs.div(rhs)?, lhs.rem(rhs)?);
+    if !rest.is_zero() && rest.is_negative() == rhs.is_negative() {
+        quotient.add(&Num::one())
+    } else {
+        Ok(quotient)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Add;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subtract;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Multiply;
+
+/// Integer division, rounding towards zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Divide;
+
+/// Bitwise exclusive or.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xor;
+
+/// Appends the digits of `rhs` to `lhs` in `base`. Only defined for
+/// non-negative values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Concatenate {
+    base: u32,
+}
+
+impl Concatenate {
+    pub const DECIMAL: Concatenate = Concatenate { base: 10 };
+
+    /// Panics if `base` is below 2, which has no digits to append.
+    pub fn new(base: u32) -> Self {
+        assert!(
+            base >= 2,
+            "concatenation base must be at least 2, got {base}"
+        );
+        Self { base }
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// `base` to the number of digits of `value` in `base`.
+    fn shift(&self, value: &Num) -> Result<Num, Overflow> {
+        let base = Num::from(i64::from(self.base));
+        let mut shift = base.clone();
+        let mut rest = value.div(&base)?;
+        while !rest.is_zero() {
+            shift = shift.mul(&base)?;
+            rest = rest.div(&base)?;
+        }
+        Ok(shift)
+    }
+}
+
+impl Default for Concatenate {
+    fn default() -> Self {
+        Self::DECIMAL
+    }
+}
+
+impl Operator for Add {
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow> {
+        lhs.add(rhs).map(Some)
+    }
+
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow> {
+        Ok(Inverse::range(first.sub(rhs)?, last.sub(rhs)?))
+    }
+
+    fn precedence(&self) -> u8 {
+        1
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+
+    fn is_monotonic(&self) -> bool {
+        true
+    }
+}
+
+impl Operator for Subtract {
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow> {
+        lhs.sub(rhs).map(Some)
+    }
+
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow> {
+        Ok(Inverse::range(first.add(rhs)?, last.add(rhs)?))
+    }
+
+    fn precedence(&self) -> u8 {
+        1
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        false
+    }
+
+    fn is_monotonic(&self) -> bool {
+        false
+    }
+}
+
+impl Operator for Multiply {
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow> {
+        lhs.mul(rhs).map(Some)
+    }
+
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow> {
+        if rhs.is_zero() {
+            return Ok(Inverse::none());
+        }
+        // The multiples of `rhs` in the range, which a negative `rhs` takes
+        // from the other end.
+        let (low, high) = if rhs.is_negative() {
+            (last, first)
+        } else {
+            (first, last)
+        };
+        Ok(Inverse::range(div_ceil(low, rhs)?, div_floor(high, rhs)?))
+    }
+
+    fn precedence(&self) -> u8 {
+        2
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+
+    fn is_monotonic(&self) -> bool {
+        true
+    }
+}
+
+impl Divide {
+    /// The first and last `lhs` with `lhs / rhs == target`, for `rhs != 0`.
+    fn lhs_of(target: &Num, rhs: &Num) -> Result<(Num, Num), Overflow> {
+        // Rounding towards zero loses less than `|rhs|`, on the side of
+        // `target * rhs` away from zero.
+        let width = if rhs.is_negative() {
+            Num::zero().sub(rhs)?
+        } else {
+            rhs.clone()
+        }
+        .sub(&Num::one())?;
+        let product = target.mul(rhs)?;
+        Ok(if product.is_zero() {
+            (Num::zero().sub(&width)?, width)
+        } else if product.is_negative() {
+            (product.sub(&width)?, product)
+        } else {
+            (product.clone(), product.add(&width)?)
+        })
+    }
+}
+
+impl Operator for Divide {
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow> {
+        if rhs.is_zero() {
+            return Ok(None);
+        }
+        lhs.div(rhs).map(Some)
+    }
+
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow> {
+        if rhs.is_zero() {
+            return Ok(Inverse::none());
+        }
+        // The quotient never goes down as `lhs` goes up, or never up for a
+        // negative `rhs`, so the ends of the range give the ends of `lhs`.
+        let (low, high) = if rhs.is_negative() {
+            (last, first)
+        } else {
+            (first, last)
+        };
+        Ok(Inverse::range(
+            Divide::lhs_of(low, rhs)?.0,
+            Divide::lhs_of(high, rhs)?.1,
+        ))
+    }
+
+    fn precedence(&self) -> u8 {
+        2
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+
+    fn is_monotonic(&self) -> bool {
+        false
+    }
+}
+
+impl Operator for Xor {
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow> {
+        lhs.xor(rhs).map(Some)
+    }
+
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow> {
+        // Split the range into blocks of a power of two aligned to their size.
+        // `^ rhs` moves each to another such block, so it takes at most two
+        // blocks per bit.
+        let (one, two) = (Num::one(), Num::from(2));
+        let mut blocks = Vec::new();
+        let mut start = first.clone();
+        while start <= *last {
+            let mut size = one.clone();
+            loop {
+                let double = size.mul(&two)?;
+                if !start.rem(&double)?.is_zero() || start.add(&double)?.sub(&one)? > *last {
+                    break;
+                }
+                size = double;
+            }
+            let mut low_bits = rhs.rem(&size)?;
+            if low_bits.is_negative() {
+                low_bits = low_bits.add(&size)?;
+            }
+            let block = start.xor(rhs)?.sub(&low_bits)?;
+            let end = block.add(&size)?.sub(&one)?;
+            blocks.push((block, end));
+            start = start.add(&size)?;
+        }
+        Ok(Inverse(blocks))
+    }
+
+    /// Below `+`, as in C.
+    fn precedence(&self) -> u8 {
+        0
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+
+    fn is_monotonic(&self) -> bool {
+        false
+    }
+}
+
+impl Operator for Concatenate {
+    fn apply(&self, lhs: &Num, rhs: &Num) -> Result<Option<Num>, Overflow> {
+        if lhs.is_negative() || rhs.is_negative() {
+            return Ok(None);
+        }
+        lhs.mul(&self.shift(rhs)?)?.add(rhs).map(Some)
+    }
+
+    fn inverse(&self, first: &Num, last: &Num, rhs: &Num) -> Result<Inverse, Overflow> {
+        if last.is_negative() || rhs.is_negative() || *last < *rhs {
+            return Ok(Inverse::none());
+        }
+        let Ok(shift) = self.shift(rhs) else {
+            // Longer than any value that fits, so no target can end in it.
+            return Ok(Inverse::none());
+        };
+        // `lhs * shift + rhs` in the range, for a non-negative `lhs`.
+        let low = div_ceil(&first.sub(rhs)?, &shift)?;
+        let low = if low.is_negative() { Num::zero() } else { low };
+        Ok(Inverse::range(low, div_floor(&last.sub(rhs)?, &shift)?))
+    }
+
+    fn precedence(&self) -> u8 {
+        3
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+
+    fn is_monotonic(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! symbol {
+    ($operator:ty, $symbol:literal) => {
+        impl fmt::Display for $operator {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, $symbol)
+            }
+        }
+    };
+}
+
+symbol!(Add, "+");
+symbol!(Subtract, "-");
+symbol!(Multiply, "*");
+symbol!(Divide, "/");
+symbol!(Xor, "^");
+
+impl fmt::Display for Concatenate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.base {
+            10 => write!(f, "||"),
+            base => write!(f, "||{base}"),
+        }
+    }
+}
+
+/// How the operators of an equation are evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Evaluation {
+    /// Strictly left to right, as in the puzzle.
+    #[default]
+    LeftToRight,
+    /// By [`Operator::precedence`], then left to right.
+    Precedence,
+}
+
+/// The operators a solver may use, and how to evaluate them.
+#[derive(Debug, Default)]
+pub struct Operators {
+    operators: Vec<Box<dyn Operator>>,
+    evaluation: Evaluation,
+}
+
+impl Operators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `+` and `*`.
+    pub fn part_1() -> Self {
+        Self::new().with(Add).with(Multiply)
+    }
+
+    /// `+`, `*` and `||`.
+    pub fn part_2() -> Self {
+        Self::part_1().with(Concatenate::DECIMAL)
+    }
+
+    pub fn with(mut self, operator: impl Operator + 'static) -> Self {
+        self.operators.push(Box::new(operator));
+        self
+    }
+
+    pub fn with_evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+
+    pub fn evaluation(&self) -> Evaluation {
+        self.evaluation
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Operator> {
+        self.operators.iter().map(|operator| operator.as_ref())
+    }
+
+    pub fn keep_non_negative(&self) -> bool {
+        self.iter().all(|operator| operator.keeps_non_negative())
+    }
+
+    pub fn are_monotonic(&self) -> bool {
+        self.iter().all(|operator| operator.is_monotonic())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(value: i64) -> Num {
+        Num::from(value)
+    }
+
+    /// The values of `inverse` from `-1000` to `1000`, in order.
+    fn listed(inverse: &Inverse) -> Vec<Num> {
+        let (low, high) = (num(-1000), num(1000));
+        let mut values = Vec::new();
+        for (first, last) in &inverse.0 {
+            let mut value = first.max(&low).clone();
+            while value <= *last.min(&high) {
+                values.push(value.clone());
+                value = value.add(&Num::one()).unwrap();
+            }
+        }
+        values.sort();
+        values
+    }
+
+    fn check(operator: &dyn Operator, lhs: i64, rhs: i64) {
+        let Some(target) = operator.apply(&num(lhs), &num(rhs)).unwrap() else {
+            return;
+        };
+        let inverse = operator.inverse(&target, &target, &num(rhs)).unwrap();
+        assert!(
+            inverse.contains(&num(lhs)),
+            "{lhs} {operator} {rhs} = {target}, inverse gave {inverse:?}"
+        );
+        for candidate in listed(&inverse) {
+            assert_eq!(
+                operator.apply(&candidate, &num(rhs)).unwrap(),
+                Some(target.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_inverses() {
+        let operators: [&dyn Operator; 7] = [
+            &Add,
+            &Subtract,
+            &Multiply,
+            &Divide,
+            &Xor,
+            &Concatenate::DECIMAL,
+            &Concatenate::new(2),
+        ];
+        for operator in operators {
+            for lhs in -40..40 {
+                for rhs in (-20..20).filter(|&rhs| rhs != 0) {
+                    check(operator, lhs, rhs);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_inverses() {
+        let operators: [&dyn Operator; 7] = [
+            &Add,
+            &Subtract,
+            &Multiply,
+            &Divide,
+            &Xor,
+            &Concatenate::DECIMAL,
+            &Concatenate::new(2),
+        ];
+        for operator in operators {
+            for (first, last) in [(-30, 40), (0, 0), (5, 17), (-9, -2), (3, 2)] {
+                for rhs in (-19..20).step_by(3) {
+                    let inverse = operator
+                        .inverse(&num(first), &num(last), &num(rhs))
+                        .unwrap();
+                    let expected = (-1000..=1000)
+                        .map(num)
+                        .filter(|lhs| {
+                            operator
+                                .apply(lhs, &num(rhs))
+                                .unwrap()
+                                .is_some_and(|value| num(first) <= value && value <= num(last))
+                        })
+                        .collect::<Vec<_>>();
+                    assert_eq!(
+                        listed(&inverse),
+                        expected,
+                        "{first}..={last} {operator} {rhs}: {inverse:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let apply = |operator: &dyn Operator, lhs, rhs| operator.apply(&num(lhs), &num(rhs));
+        assert_eq!(apply(&Concatenate::DECIMAL, 15, 6), Ok(Some(num(156))));
+        assert_eq!(apply(&Concatenate::DECIMAL, 1, 0), Ok(Some(num(10))));
+        assert_eq!(
+            apply(&Concatenate::new(2), 0b101, 0b11),
+            Ok(Some(num(0b10111)))
+        );
+        assert_eq!(apply(&Concatenate::new(16), 1, 255), Ok(Some(num(0x1ff))));
+        assert_eq!(apply(&Divide, 7, 2), Ok(Some(num(3))));
+        assert_eq!(apply(&Divide, 7, 0), Ok(None));
+        assert_eq!(apply(&Xor, 6, 3), Ok(Some(num(5))));
+        assert_eq!(apply(&Subtract, 2, 3), Ok(Some(num(-1))));
+    }
+
+    #[test]
+    fn test_divide_inverse_is_a_range() {
+        let inverse = |target, rhs| {
+            Divide
+                .inverse(&num(target), &num(target), &num(rhs))
+                .unwrap()
+        };
+        assert_eq!(inverse(3, 4), Inverse::range(num(12), num(15)));
+        assert_eq!(inverse(-3, 4), Inverse::range(num(-15), num(-12)));
+        assert_eq!(inverse(3, -4), Inverse::range(num(-15), num(-12)));
+        assert_eq!(inverse(0, -4), Inverse::range(num(-3), num(3)));
+        assert_eq!(inverse(5, 1), Inverse::range(num(5), num(5)));
+        let huge = 1_000_000_000_000_000;
+        assert_eq!(
+            inverse(2, huge),
+            Inverse::range(num(2 * huge), num(3 * huge - 1))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2")]
+    fn test_concatenate_base_1() {
+        Concatenate::new(1);
+    }
+
+    #[test]
+    fn test_display() {
+        let operators = Operators::part_2()
+            .with(Subtract)
+            .with(Divide)
+            .with(Xor)
+            .with(Concatenate::new(2));
+        let symbols = operators.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+        assert_eq!(symbols, vec!["+", "*", "||", "-", "/", "^", "||2"]);
+        assert!(!operators.keep_non_negative());
+        assert!(Operators::part_2().keep_non_negative());
+    }
+}
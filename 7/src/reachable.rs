@@ -0,0 +1,208 @@
+//! Searches over the sets of values reachable at each position.
+//!
+//! Operator sequences that lead to the same intermediate state are merged
+//! into one entry with a count, so the work grows with the number of distinct
+//! states rather than with the number of sequences.
+//!
+//! With left-to-right evaluation the search meets in the middle: values
+//! reachable from the left, and values needed to reach the target from the
+//! right, each growing by one number at a time from whichever set is smaller.
+//! With precedence no partial expression can be undone, so the states are the
+//! value and operator stacks of the evaluation, from the left only.
+
+use crate::operator::{Evaluation, Operators};
+use crate::{reduce, Equation};
+use aoc_2024_lib::num::{Num, Overflow};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Number of operator sequences that solve `equation`, saturating at
+/// `usize::MAX`. Like backtracking, it may miss solutions that need an
+/// operator undone in infinitely many ways, like `* 0`.
+pub(crate) fn count_solutions(
+    equation: &Equation,
+    operators: &Operators,
+) -> Result<usize, Overflow> {
+    match operators.evaluation() {
+        Evaluation::LeftToRight => meet_in_the_middle(equation, operators),
+        Evaluation::Precedence => forward_stacks(equation, operators),
+    }
+}
+
+fn merge<K: Eq + Hash>(states: &mut HashMap<K, usize>, state: K, count: usize) {
+    let merged = states.entry(state).or_default();
+    *merged = merged.saturating_add(count);
+}
+
+fn meet_in_the_middle(equation: &Equation, operators: &Operators) -> Result<usize, Overflow> {
+    let numbers = &equation.numbers;
+    // Values never shrink, so any above the target is a dead end.
+    let bounded = operators.are_monotonic() && numbers.iter().all(|number| *number >= Num::one());
+    let non_negative =
+        operators.keep_non_negative() && numbers.iter().all(|number| !number.is_negative());
+
+    // Values of `numbers[..=left]`, and values `numbers[..=right]` must have
+    // for the rest to give the target. Ranges of the latter, as division
+    // leaves, stay ranges and are never listed value by value.
+    let (mut left, mut right) = (0, numbers.len() - 1);
+    let mut forward = HashMap::from([(numbers[0].clone(), 1)]);
+    let mut backward = HashMap::from([(equation.target.clone(), 1)]);
+    let mut ranges: Vec<(Num, Num, usize)> = Vec::new();
+    while left < right && !forward.is_empty() && !(backward.is_empty() && ranges.is_empty()) {
+        if forward.len() <= backward.len() + ranges.len() {
+            left += 1;
+            let mut next = HashMap::with_capacity(forward.len());
+            for (value, count) in forward {
+                for operator in operators.iter() {
+                    match operator.apply(&value, &numbers[left])? {
+                        Some(value) if !(bounded && value > equation.target) => {
+                            merge(&mut next, value, count)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            forward = next;
+        } else {
+            let mut next = HashMap::with_capacity(backward.len());
+            let mut next_ranges = Vec::new();
+            let values = backward
+                .into_iter()
+                .map(|(value, count)| (value.clone(), value, count));
+            for (first, last, count) in values.chain(ranges) {
+                for operator in operators.iter() {
+                    for (first, last) in operator.inverse(&first, &last, &numbers[right])?.0 {
+                        let first = if non_negative && first.is_negative() {
+                            Num::zero()
+                        } else {
+                            first
+                        };
+                        if first == last {
+                            merge(&mut next, first, count);
+                        } else if first < last {
+                            next_ranges.push((first, last, count));
+                        }
+                    }
+                }
+            }
+            backward = next;
+            ranges = next_ranges;
+            right -= 1;
+        }
+    }
+
+    let mut total = forward
+        .iter()
+        .filter_map(|(value, count)| Some(count.saturating_mul(*backward.get(value)?)))
+        .fold(0, usize::saturating_add);
+    let mut sorted = forward.into_iter().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    for (first, last, count) in ranges {
+        let start = sorted.partition_point(|(value, _)| *value < first);
+        let end = sorted.partition_point(|(value, _)| *value <= last);
+        for (_, forward_count) in &sorted[start..end] {
+            total = total.saturating_add(count.saturating_mul(*forward_count));
+        }
+    }
+    Ok(total)
+}
+
+/// Values not yet combined, and indices of the operators between them.
+type Stacks = (Vec<Num>, Vec<usize>);
+
+fn forward_stacks(equation: &Equation, operators: &Operators) -> Result<usize, Overflow> {
+    let all = operators.iter().collect::<Vec<_>>();
+    let operator = |index: usize| all[index];
+
+    let mut states: HashMap<Stacks, usize> =
+        HashMap::from([((vec![equation.numbers[0].clone()], Vec::new()), 1)]);
+    for number in &equation.numbers[1..] {
+        let mut next = HashMap::with_capacity(states.len());
+        for ((values, pending), count) in states {
+            for (index, op) in all.iter().enumerate() {
+                let (mut values, mut pending) = (values.clone(), pending.clone());
+                if !reduce(&mut values, &mut pending, operator, op.precedence())? {
+                    continue;
+                }
+                pending.push(index);
+                values.push(number.clone());
+                merge(&mut next, (values, pending), count);
+            }
+        }
+        states = next;
+    }
+
+    let mut total = 0_usize;
+    for ((mut values, mut pending), count) in states {
+        if reduce(&mut values, &mut pending, operator, 0)?
+            && values.pop().as_ref() == Some(&equation.target)
+        {
+            total = total.saturating_add(count);
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::{Concatenate, Divide, Subtract, Xor};
+
+    fn num(value: i64) -> Num {
+        Num::from(value)
+    }
+
+    #[test]
+    fn test_matches_backtracking() {
+        let sets = [
+            Operators::part_1(),
+            Operators::part_2(),
+            Operators::part_2()
+                .with(Subtract)
+                .with(Divide)
+                .with(Xor)
+                .with(Concatenate::new(2)),
+            Operators::part_2()
+                .with(Subtract)
+                .with_evaluation(Evaluation::Precedence),
+        ];
+        let mut seed = 43_u64;
+        for operators in &sets {
+            for _ in 0..200 {
+                let mut next = |bound: u64| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    (seed >> 33) % bound
+                };
+                let count = 2 + next(4) as usize;
+                let numbers = (0..count)
+                    .map(|_| num(1 + next(9) as i64))
+                    .collect::<Vec<_>>();
+                let target = num(next(80) as i64 - 10);
+                let eq = Equation { target, numbers };
+                assert_eq!(
+                    count_solutions(&eq, operators),
+                    eq.count_solutions(operators),
+                    "{eq:?} with {operators:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_many_operands() {
+        // 2^39 sequences of `+` and `*`: only all `+` gives 40, and only
+        // all `*` gives 1.
+        let eq = Equation {
+            target: num(40),
+            numbers: vec![num(1); 40],
+        };
+        assert_eq!(count_solutions(&eq, &Operators::part_1()), Ok(1));
+        let eq = Equation {
+            target: num(1),
+            numbers: vec![num(1); 40],
+        };
+        assert_eq!(count_solutions(&eq, &Operators::part_1()), Ok(1));
+    }
+}
@@ -0,0 +1,361 @@
+//! Where antennas create antinodes.
+//!
+//! Every pair of resonating antennas `a` and `b` puts antinodes on the line
+//! through them, at `a + t * (b - a)` for the values of `t` picked by the
+//! [`Harmonics`]. Antennas resonate with others of the same frequency, and
+//! with those of any frequency sharing a channel with theirs.
+
+use crate::line::{distinct_points, Line};
+use crate::point::Point;
+use std::collections::{BTreeMap, HashSet};
+
+/// Antenna positions by frequency.
+pub type Antennas = BTreeMap<char, Vec<Point>>;
+
+/// The area antinodes have to fall in, from `(0, 0)` to just before
+/// `(height, width)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub height: isize,
+    pub width: isize,
+}
+
+impl Bounds {
+    pub fn contains(&self, point: Point) -> bool {
+        point.row >= 0 && point.col >= 0 && point.row < self.height && point.col < self.width
+    }
+}
+
+/// Which points on the line through a pair of antennas are antinodes. Both
+/// antennas of a pair take the role of `a` in turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Harmonics {
+    /// `a + k * (b - a)` for each `k`. Part 1 is `[-1, 2]`.
+    Multiples(Vec<isize>),
+    /// `a + (n / d) * (b - a)` for each `(n, d)`, where that is a grid point.
+    /// `(1, 3)` is between the antennas and twice as far from `b` as from `a`.
+    Fractions(Vec<(isize, isize)>),
+    /// Every grid point in line with the antennas, that is every multiple of
+    /// `b - a` divided by the gcd of its coordinates. Part 2. Coinciding
+    /// antennas have their own position as the only antinode.
+    AllMultiples,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resonance {
+    harmonics: Harmonics,
+    channels: Vec<Vec<char>>,
+}
+
+impl Resonance {
+    pub fn new(harmonics: Harmonics) -> Self {
+        Self {
+            harmonics,
+            channels: Vec::new(),
+        }
+    }
+
+    /// Antinodes twice as far from one antenna as from the other, outside
+    /// the pair.
+    pub fn part_1() -> Self {
+        Self::new(Harmonics::Multiples(vec![-1, 2]))
+    }
+
+    /// Antinodes anywhere in line with the pair.
+    pub fn part_2() -> Self {
+        Self::new(Harmonics::AllMultiples)
+    }
+
+    /// Makes every frequency in `frequencies` resonate with the others.
+    pub fn with_channel(mut self, frequencies: &str) -> Self {
+        self.channels.push(frequencies.chars().collect());
+        self
+    }
+
+    pub fn harmonics(&self) -> &Harmonics {
+        &self.harmonics
+    }
+
+    /// Whether antennas of frequencies `a` and `b` form pairs.
+    pub fn resonates(&self, a: char, b: char) -> bool {
+        a == b
+            || self
+                .channels
+                .iter()
+                .any(|channel| channel.contains(&a) && channel.contains(&b))
+    }
+
+    /// The antinodes inside `bounds` of every resonating pair. Those of a
+    /// pair across two frequencies count for both.
+    pub fn antinodes(&self, antennas: &Antennas, bounds: Bounds) -> Antinodes {
+        let mut by_frequency = antennas
+            .keys()
+            .map(|&frequency| (frequency, HashSet::new()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut found = Vec::new();
+        self.for_each_pair(antennas, |f, g, a, b| {
+            found.clear();
+            self.pair(a, b, bounds, &mut found);
+            for frequency in [f, g] {
+                by_frequency
+                    .get_mut(&frequency)
+                    .unwrap()
+                    .extend(found.iter().copied());
+            }
+        });
+
+        Antinodes { by_frequency }
+    }
+
+    /// The number of antinodes [`Resonance::antinodes`] would find, without
+    /// listing those of [`Harmonics::AllMultiples`] one by one. On a big map
+    /// they far outnumber the antennas.
+    pub fn count(&self, antennas: &Antennas, bounds: Bounds) -> Counts {
+        if self.harmonics != Harmonics::AllMultiples {
+            let antinodes = self.antinodes(antennas, bounds);
+            return Counts {
+                by_frequency: antinodes
+                    .iter()
+                    .map(|(frequency, points)| (frequency, points.len()))
+                    .collect(),
+                distinct: antinodes.count(),
+            };
+        }
+
+        let mut lines = antennas
+            .keys()
+            .map(|&frequency| (frequency, (HashSet::new(), HashSet::new())))
+            .collect::<BTreeMap<_, _>>();
+        self.for_each_pair(antennas, |f, g, a, b| {
+            for frequency in [f, g] {
+                let (lines, points) = lines.get_mut(&frequency).unwrap();
+                match Line::through(a, b, bounds) {
+                    Some(line) => {
+                        lines.insert(line);
+                    }
+                    None if a == b && bounds.contains(a) => {
+                        points.insert(a);
+                    }
+                    None => {}
+                }
+            }
+        });
+
+        Counts {
+            by_frequency: lines
+                .iter()
+                .map(|(&frequency, (lines, points))| (frequency, distinct_points(lines, points)))
+                .collect(),
+            distinct: distinct_points(
+                lines.values().flat_map(|(lines, _)| lines),
+                lines.values().flat_map(|(_, points)| points),
+            ),
+        }
+    }
+
+    /// Calls `visit` with the frequencies and positions of every resonating
+    /// pair of antennas.
+    fn for_each_pair(&self, antennas: &Antennas, mut visit: impl FnMut(char, char, Point, Point)) {
+        let frequencies = antennas.iter().collect::<Vec<_>>();
+        for (i, &(&f, positions_f)) in frequencies.iter().enumerate() {
+            for &(&g, positions_g) in &frequencies[i..] {
+                if !self.resonates(f, g) {
+                    continue;
+                }
+                for (j, &a) in positions_f.iter().enumerate() {
+                    let partners = if f == g {
+                        &positions_g[j + 1..]
+                    } else {
+                        &positions_g[..]
+                    };
+                    for &b in partners {
+                        visit(f, g, a, b);
+                    }
+                }
+            }
+        }
+    }
+
+    fn pair(&self, a: Point, b: Point, bounds: Bounds, found: &mut Vec<Point>) {
+        let mut push = |point: Point| {
+            if bounds.contains(point) {
+                found.push(point);
+            }
+        };
+        let delta = b - a;
+        match &self.harmonics {
+            Harmonics::Multiples(multiples) => {
+                for &k in multiples {
+                    push(a + delta * k);
+                    push(b - delta * k);
+                }
+            }
+            Harmonics::Fractions(fractions) => {
+                for &(n, d) in fractions {
+                    let scaled = delta * n;
+                    if d != 0 && scaled.row % d == 0 && scaled.col % d == 0 {
+                        push(a + scaled / d);
+                        push(b - scaled / d);
+                    }
+                }
+            }
+            Harmonics::AllMultiples => match Line::through(a, b, bounds) {
+                Some(line) => line.points().for_each(push),
+                None => push(a),
+            },
+        }
+    }
+}
+
+/// Antinode counts by the frequency of the antennas creating them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counts {
+    pub by_frequency: BTreeMap<char, usize>,
+    /// Positions with an antinode of any frequency, the puzzle answer.
+    pub distinct: usize,
+}
+
+/// Antinode positions by the frequency of the antennas creating them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Antinodes {
+    by_frequency: BTreeMap<char, HashSet<Point>>,
+}
+
+impl Antinodes {
+    /// The antinodes of `frequency`, empty for one without antennas.
+    pub fn of(&self, frequency: char) -> impl Iterator<Item = Point> + '_ {
+        self.by_frequency
+            .get(&frequency)
+            .into_iter()
+            .flat_map(|points| points.iter().copied())
+    }
+
+    /// Each frequency with antennas and its antinodes, in frequency order.
+    pub fn iter(&self) -> impl Iterator<Item = (char, &HashSet<Point>)> {
+        self.by_frequency
+            .iter()
+            .map(|(&frequency, points)| (frequency, points))
+    }
+
+    /// Positions with an antinode of any frequency.
+    pub fn merged(&self) -> HashSet<Point> {
+        self.by_frequency.values().flatten().copied().collect()
+    }
+
+    /// Number of distinct positions with an antinode, the puzzle answer.
+    pub fn count(&self) -> usize {
+        self.merged().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Bounds = Bounds {
+        height: 10,
+        width: 10,
+    };
+
+    fn antennas(list: &[(char, isize, isize)]) -> Antennas {
+        let mut antennas = Antennas::new();
+        for &(frequency, row, col) in list {
+            antennas
+                .entry(frequency)
+                .or_default()
+                .push(Point::new(row, col));
+        }
+        antennas
+    }
+
+    fn sorted(points: impl Iterator<Item = Point>) -> Vec<(isize, isize)> {
+        let mut points = points.map(|p| (p.row, p.col)).collect::<Vec<_>>();
+        points.sort();
+        points
+    }
+
+    #[test]
+    fn test_part_1() {
+        // The pair from the puzzle description.
+        let antennas = antennas(&[('a', 3, 4), ('a', 5, 5)]);
+        let antinodes = Resonance::part_1().antinodes(&antennas, BOUNDS);
+        assert_eq!(sorted(antinodes.of('a')), vec![(1, 3), (7, 6)]);
+    }
+
+    #[test]
+    fn test_part_2() {
+        // The T antennas from the puzzle description.
+        let antennas = antennas(&[('T', 0, 0), ('T', 1, 3), ('T', 2, 1)]);
+        let antinodes = Resonance::part_2().antinodes(&antennas, BOUNDS);
+        assert_eq!(antinodes.count(), 9);
+    }
+
+    #[test]
+    fn test_fractions() {
+        let antennas = antennas(&[('a', 0, 0), ('a', 6, 3)]);
+        let thirds = Resonance::new(Harmonics::Fractions(vec![(1, 3), (2, 3)]));
+        assert_eq!(
+            sorted(thirds.antinodes(&antennas, BOUNDS).of('a')),
+            vec![(2, 1), (4, 2)]
+        );
+        // Halfway is not a grid point.
+        let half = Resonance::new(Harmonics::Fractions(vec![(1, 2)]));
+        assert_eq!(half.antinodes(&antennas, BOUNDS).count(), 0);
+    }
+
+    #[test]
+    fn test_multiples_are_symmetric() {
+        let antennas = antennas(&[('a', 4, 4), ('a', 5, 5)]);
+        let resonance = Resonance::new(Harmonics::Multiples(vec![3]));
+        assert_eq!(
+            sorted(resonance.antinodes(&antennas, BOUNDS).of('a')),
+            vec![(2, 2), (7, 7)]
+        );
+    }
+
+    #[test]
+    fn test_count_matches_antinodes() {
+        let mut seed = 8_u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % bound) as isize
+        };
+        for _ in 0..50 {
+            let list = (0..12)
+                .map(|_| (b"abA"[next(3) as usize] as char, next(10), next(10)))
+                .collect::<Vec<_>>();
+            let antennas = antennas(&list);
+            for resonance in [
+                Resonance::part_1(),
+                Resonance::part_2(),
+                Resonance::part_2().with_channel("aA"),
+            ] {
+                let antinodes = resonance.antinodes(&antennas, BOUNDS);
+                let counts = resonance.count(&antennas, BOUNDS);
+                assert_eq!(counts.distinct, antinodes.count(), "{list:?}");
+                for (frequency, points) in antinodes.iter() {
+                    assert_eq!(counts.by_frequency[&frequency], points.len(), "{list:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_channels() {
+        let antennas = antennas(&[('a', 3, 4), ('A', 5, 5), ('b', 0, 9)]);
+        let separate = Resonance::part_1().antinodes(&antennas, BOUNDS);
+        assert_eq!(separate.count(), 0);
+        assert_eq!(separate.iter().count(), 3);
+
+        let coupled = Resonance::part_1()
+            .with_channel("aA")
+            .antinodes(&antennas, BOUNDS);
+        assert_eq!(sorted(coupled.of('a')), vec![(1, 3), (7, 6)]);
+        assert_eq!(sorted(coupled.of('A')), vec![(1, 3), (7, 6)]);
+        assert_eq!(sorted(coupled.of('b')), vec![]);
+        assert_eq!(coupled.count(), 2);
+    }
+}
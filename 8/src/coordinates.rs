@@ -0,0 +1,210 @@
+//! Antenna maps as a list of coordinates, for maps too big to draw.
+//!
+//! The first line holds the height and width of the map, every other line an
+//! antenna as its frequency, row and column. Blank lines and lines starting
+//! with `#` are skipped:
+//!
+//! ```text
+//! # the example from the puzzle
+//! 12 12
+//! 0 1 8
+//! A 5 6
+//! ```
+
+use crate::antinode::{Antennas, Bounds};
+use crate::point::Point;
+use aoc_2024_lib::rng::Rng;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based.
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn parse_coordinates(input: &str) -> Result<(Bounds, Antennas), ParseError> {
+    let mut bounds = None;
+    let mut antennas = Antennas::new();
+    let mut seen = HashSet::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        if fields.is_empty() || fields[0].starts_with('#') {
+            continue;
+        }
+        let error = |reason: String| ParseError {
+            line: index + 1,
+            reason,
+        };
+        let number = |field: &str| {
+            field
+                .parse::<isize>()
+                .map_err(|e| error(format!("invalid number {field:?}: {e}")))
+        };
+
+        let Some(bounds) = bounds else {
+            let [height, width] = fields[..] else {
+                return Err(error("expected the height and width".to_string()));
+            };
+            bounds = Some(Bounds {
+                height: number(height)?,
+                width: number(width)?,
+            });
+            continue;
+        };
+
+        let [frequency, row, col] = fields[..] else {
+            return Err(error("expected a frequency, row and column".to_string()));
+        };
+        let mut chars = frequency.chars();
+        let (Some(frequency), None) = (chars.next(), chars.next()) else {
+            return Err(error(format!(
+                "frequency {frequency:?} is not one character"
+            )));
+        };
+        let point = Point::new(number(row)?, number(col)?);
+        if !bounds.contains(point) {
+            return Err(error(format!("({row}, {col}) is outside the map")));
+        }
+        if !seen.insert(point) {
+            return Err(error(format!("a second antenna at ({row}, {col})")));
+        }
+        antennas.entry(frequency).or_default().push(point);
+    }
+
+    let bounds = bounds.ok_or(ParseError {
+        line: input.lines().count(),
+        reason: "missing the height and width".to_string(),
+    })?;
+    Ok((bounds, antennas))
+}
+
+pub fn write_coordinates(
+    out: &mut impl Write,
+    bounds: Bounds,
+    antennas: &Antennas,
+) -> io::Result<()> {
+    writeln!(out, "{} {}", bounds.height, bounds.width)?;
+    for (frequency, points) in antennas {
+        for point in points {
+            writeln!(out, "{frequency} {} {}", point.row, point.col)?;
+        }
+    }
+    Ok(())
+}
+
+/// Frequencies as in the puzzle: digits and letters.
+const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Writes a map of `bounds` with `count` antennas at random, distinct
+/// positions. `count` must leave some of the map free.
+pub fn write_generated(
+    out: &mut impl Write,
+    bounds: Bounds,
+    count: usize,
+    seed: u64,
+) -> io::Result<()> {
+    let mut rng = Rng::new(seed);
+    let mut next = |bound: isize| rng.below(bound as u64) as isize;
+
+    let mut antennas = Antennas::new();
+    let mut seen = HashSet::new();
+    while seen.len() < count {
+        let point = Point::new(next(bounds.height), next(bounds.width));
+        if seen.insert(point) {
+            let frequency = FREQUENCIES[next(FREQUENCIES.len() as isize) as usize] as char;
+            antennas.entry(frequency).or_default().push(point);
+        }
+    }
+    write_coordinates(out, bounds, &antennas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antinode::Resonance;
+    use crate::{find_antennas, parse_input};
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn test_round_trip() {
+        let grid = parse_input(EXAMPLE);
+        let antennas = find_antennas(&grid);
+        let mut written = Vec::new();
+        write_coordinates(&mut written, grid.bounds(), &antennas).unwrap();
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.starts_with("12 12\n0 1 8\n"));
+
+        let parsed = parse_coordinates(&format!("# example\n\n{written}")).unwrap();
+        assert_eq!(parsed, (grid.bounds(), antennas));
+        assert_eq!(Resonance::part_1().count(&parsed.1, parsed.0).distinct, 14);
+        assert_eq!(Resonance::part_2().count(&parsed.1, parsed.0).distinct, 34);
+    }
+
+    #[test]
+    fn test_errors() {
+        let line = |input: &str| parse_coordinates(input).unwrap_err().to_string();
+        assert_eq!(line("12\n"), "line 1: expected the height and width");
+        assert_eq!(
+            line("# only a comment\n"),
+            "line 1: missing the height and width"
+        );
+        assert_eq!(
+            line("5 5\na 1\n"),
+            "line 2: expected a frequency, row and column"
+        );
+        assert_eq!(
+            line("5 5\nab 1 1\n"),
+            "line 2: frequency \"ab\" is not one character"
+        );
+        assert_eq!(line("5 5\na 1 5\n"), "line 2: (1, 5) is outside the map");
+        assert_eq!(
+            line("5 5\na 1 1\n\nb 1 1\n"),
+            "line 4: a second antenna at (1, 1)"
+        );
+        assert!(line("5 x\n").starts_with("line 1: invalid number \"x\""));
+    }
+
+    #[test]
+    fn test_large_sparse_map() {
+        let bounds = Bounds {
+            height: 1_000_000,
+            width: 1_000_000,
+        };
+        let mut input = Vec::new();
+        write_generated(&mut input, bounds, 400, 8).unwrap();
+        let (parsed, antennas) = parse_coordinates(std::str::from_utf8(&input).unwrap()).unwrap();
+        assert_eq!(parsed, bounds);
+        assert_eq!(antennas.values().map(Vec::len).sum::<usize>(), 400);
+
+        // Lines through random points are mostly short, so listing the
+        // antinodes is still feasible to check the counts against.
+        let resonance = Resonance::part_2();
+        let counts = resonance.count(&antennas, bounds);
+        let antinodes = resonance.antinodes(&antennas, bounds);
+        assert_eq!(counts.distinct, antinodes.count());
+        for (frequency, points) in antinodes.iter() {
+            assert_eq!(counts.by_frequency[&frequency], points.len());
+        }
+
+        // A full diagonal, and a line that misses it between grid points.
+        let (bounds, antennas) =
+            parse_coordinates("1000000 1000000\na 0 0\na 1 1\nb 0 1\nb 1 0\n").unwrap();
+        let counts = resonance.count(&antennas, bounds);
+        assert_eq!(counts.by_frequency[&'a'], 1_000_000);
+        assert_eq!(counts.by_frequency[&'b'], 2);
+        assert_eq!(counts.distinct, 1_000_002);
+    }
+}
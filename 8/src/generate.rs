@@ -0,0 +1,31 @@
+//! Writes a generated antenna list to stdout:
+//! `generate HEIGHT WIDTH ANTENNAS [SEED]`.
+
+use aoc_2024_8::antinode::Bounds;
+use aoc_2024_8::coordinates::write_generated;
+use std::io::{self, BufWriter, Write};
+
+const USAGE: &str = "Usage: generate HEIGHT WIDTH ANTENNAS [SEED]";
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut parse = || args.next().map(|arg| arg.parse::<u64>());
+    let (Some(Ok(height)), Some(Ok(width)), Some(Ok(count)), Ok(seed)) =
+        (parse(), parse(), parse(), parse().unwrap_or(Ok(2024)))
+    else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+    if count >= height * width {
+        eprintln!("{USAGE}\nANTENNAS has to leave some of the map free");
+        std::process::exit(2);
+    }
+
+    let bounds = Bounds {
+        height: height as isize,
+        width: width as isize,
+    };
+    let mut out = BufWriter::new(io::stdout().lock());
+    write_generated(&mut out, bounds, count as usize, seed)?;
+    out.flush()
+}
@@ -1,3 +1,4 @@
+use crate::antinode::Bounds;
 use std::fmt::Display;
 
 #[derive(Debug)]
@@ -45,4 +46,11 @@ impl<T: Clone + Display> Grid<T> {
     pub fn width(&self) -> usize {
         self.width
     }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds {
+            height: self.height as isize,
+            width: self.width as isize,
+        }
+    }
 }
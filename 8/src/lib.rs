@@ -0,0 +1,29 @@
+use antinode::Antennas;
+use grid::Grid;
+use point::Point;
+
+pub mod antinode;
+pub mod coordinates;
+pub mod grid;
+pub mod line;
+pub mod point;
+pub mod render;
+
+pub fn parse_input(input: &str) -> Grid<char> {
+    Grid::new(
+        input.lines().map(|line| line.chars().collect()).collect(),
+        ' ',
+    )
+}
+
+pub fn find_antennas(grid: &Grid<char>) -> Antennas {
+    let mut antennas = Antennas::new();
+
+    for (row, col, &ch) in grid.cells() {
+        if ch != '.' && ch != ' ' {
+            antennas.entry(ch).or_default().push(Point::new(row, col));
+        }
+    }
+
+    antennas
+}
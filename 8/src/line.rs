@@ -0,0 +1,260 @@
+//! Lines of grid points, clipped to the bounds without stepping along them.
+//!
+//! When antinodes can be anywhere in line with a pair of antennas, a big map
+//! has far more antinodes than antennas. Counting them goes by lines instead:
+//! the points of each distinct long line, less those counted again where
+//! lines cross, plus the points of the short lines that are on none of them.
+
+use crate::antinode::Bounds;
+use crate::point::Point;
+use std::collections::{HashMap, HashSet};
+
+/// The grid points `origin + k * step` for `k` in `0..len`, all inside the
+/// bounds the line was clipped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line {
+    /// The first point inside the bounds.
+    pub origin: Point,
+    /// Divided by the gcd of its coordinates, pointing down or, along a row,
+    /// right.
+    pub step: Point,
+    pub len: isize,
+}
+
+impl Line {
+    /// The line through `a` and `b` clipped to `bounds`. `None` if the points
+    /// coincide or the line misses the bounds.
+    pub fn through(a: Point, b: Point, bounds: Bounds) -> Option<Line> {
+        let delta = b - a;
+        if delta == Point::new(0, 0) {
+            return None;
+        }
+        let mut step = delta / gcd(delta.row, delta.col);
+        if step.row < 0 || (step.row == 0 && step.col < 0) {
+            step = Point::new(0, 0) - step;
+        }
+        let (first, last) = clip(a, step, bounds)?;
+        Some(Line {
+            origin: a + step * first,
+            step,
+            len: last - first + 1,
+        })
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.len).map(move |k| self.origin + self.step * k)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let offset = point - self.origin;
+        if cross(offset, self.step) != 0 {
+            return false;
+        }
+        // `step` is reduced, so anything in line with it is a multiple of it.
+        let k = if self.step.row != 0 {
+            offset.row / self.step.row
+        } else {
+            offset.col / self.step.col
+        };
+        (0..self.len).contains(&k)
+    }
+
+    /// The point the two lines share, if there is exactly one.
+    pub fn crossing(&self, other: &Line) -> Option<Point> {
+        // origin + k * step = other.origin + j * other.step, crossed with
+        // other.step to get rid of j.
+        let denominator = cross(self.step, other.step);
+        if denominator == 0 {
+            return None;
+        }
+        let numerator = cross(other.origin - self.origin, other.step);
+        if numerator % denominator != 0 {
+            return None;
+        }
+        let point = self.origin + self.step * (numerator / denominator);
+        (self.contains(point) && other.contains(point)).then_some(point)
+    }
+}
+
+/// Lines up to this long are cheaper to list than to cross with the others.
+const SHORT: isize = 1024;
+
+/// Number of distinct points on `lines` or in `points`.
+pub fn distinct_points<'a>(
+    lines: impl IntoIterator<Item = &'a Line>,
+    points: impl IntoIterator<Item = &'a Point>,
+) -> usize {
+    let (long, short): (Vec<Line>, Vec<Line>) = lines
+        .into_iter()
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .partition(|line| line.len > SHORT);
+
+    // A point on m lines is counted m times and found by m(m - 1) / 2 pairs.
+    let mut crossings: HashMap<Point, usize> = HashMap::new();
+    for (i, a) in long.iter().enumerate() {
+        for b in &long[i + 1..] {
+            if let Some(point) = a.crossing(b) {
+                *crossings.entry(point).or_default() += 1;
+            }
+        }
+    }
+    let counted = long.iter().map(|line| line.len as usize).sum::<usize>();
+    let repeated = crossings
+        .values()
+        .map(|&pairs| (1 + 8 * pairs).isqrt().div_ceil(2) - 1)
+        .sum::<usize>();
+
+    let listed = short
+        .iter()
+        .flat_map(|line| line.points())
+        .chain(points.into_iter().copied())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter(|&point| !long.iter().any(|line| line.contains(point)))
+        .count();
+    counted - repeated + listed
+}
+
+/// The first and last `k` with `start + k * step` inside `bounds`.
+fn clip(start: Point, step: Point, bounds: Bounds) -> Option<(isize, isize)> {
+    let (mut first, mut last) = (isize::MIN, isize::MAX);
+    for (x, s, n) in [
+        (start.row, step.row, bounds.height),
+        (start.col, step.col, bounds.width),
+    ] {
+        // 0 <= x + k * s <= n - 1
+        let (low, high) = match s {
+            0 if (0..n).contains(&x) => continue,
+            0 => return None,
+            1.. => (ceil_div(-x, s), floor_div(n - 1 - x, s)),
+            _ => (ceil_div(n - 1 - x, s), floor_div(-x, s)),
+        };
+        first = first.max(low);
+        last = last.min(high);
+    }
+    (first <= last).then_some((first, last))
+}
+
+fn floor_div(a: isize, b: isize) -> isize {
+    let quotient = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+fn ceil_div(a: isize, b: isize) -> isize {
+    -floor_div(-a, b)
+}
+
+fn cross(a: Point, b: Point) -> isize {
+    a.row * b.col - a.col * b.row
+}
+
+fn gcd(mut a: isize, mut b: isize) -> isize {
+    a = a.abs();
+    b = b.abs();
+    while b != 0 {
+        let temp = b;
+        b = a % b;
+        a = temp;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Bounds = Bounds {
+        height: 10,
+        width: 12,
+    };
+
+    fn line(a: (isize, isize), b: (isize, isize)) -> Line {
+        Line::through(Point::new(a.0, a.1), Point::new(b.0, b.1), BOUNDS).unwrap()
+    }
+
+    #[test]
+    fn test_clipping() {
+        let diagonal = line((5, 5), (3, 1));
+        assert_eq!(diagonal.step, Point::new(1, 2));
+        assert_eq!(diagonal.origin, Point::new(3, 1));
+        assert_eq!(diagonal.len, 6);
+        assert_eq!(diagonal, line((4, 3), (6, 7)));
+
+        let row = line((3, 7), (3, 2));
+        assert_eq!(
+            (row.origin, row.step, row.len),
+            (Point::new(3, 0), Point::new(0, 1), 12)
+        );
+
+        for line in [diagonal, row, line((9, 0), (8, 3)), line((2, 2), (5, 3))] {
+            let stepped = (-30..30)
+                .map(|k| line.origin + line.step * k)
+                .filter(|&point| BOUNDS.contains(point))
+                .collect::<Vec<_>>();
+            assert_eq!(line.points().collect::<Vec<_>>(), stepped);
+        }
+    }
+
+    #[test]
+    fn test_crossings() {
+        let diagonal = line((0, 0), (1, 1));
+        let anti = line((0, 9), (1, 8));
+        assert_eq!(diagonal.crossing(&anti), None); // would be (4.5, 4.5)
+        let anti = line((0, 8), (1, 7));
+        assert_eq!(diagonal.crossing(&anti), Some(Point::new(4, 4)));
+        assert_eq!(diagonal.crossing(&diagonal), None);
+        // Skips the middle (1, 1.5) of the steep one.
+        assert_eq!(line((0, 0), (2, 3)).crossing(&line((1, 0), (1, 1))), None);
+        assert_eq!(
+            line((0, 0), (2, 3)).crossing(&line((2, 0), (2, 1))),
+            Some(Point::new(2, 3))
+        );
+    }
+
+    #[test]
+    fn test_distinct_points() {
+        // Three lines through (4, 4), plus a point on one of them and one
+        // off all of them. None are long enough to be crossed analytically.
+        let lines = [
+            line((0, 0), (1, 1)),
+            line((4, 0), (4, 1)),
+            line((0, 4), (1, 4)),
+        ];
+        let points = [Point::new(4, 7), Point::new(9, 0)];
+        assert_eq!(distinct_points(&lines, &points), 10 + 12 + 10 - 2 + 1);
+
+        let brute = lines
+            .iter()
+            .flat_map(|line| line.points())
+            .chain(points)
+            .collect::<HashSet<_>>();
+        assert_eq!(brute.len(), 31);
+
+        let bounds = Bounds {
+            height: 5000,
+            width: 5000,
+        };
+        let long = |a: (isize, isize), b: (isize, isize)| {
+            Line::through(Point::new(a.0, a.1), Point::new(b.0, b.1), bounds).unwrap()
+        };
+        let lines = [
+            long((0, 0), (1, 1)),
+            long((400, 0), (400, 1)),
+            long((0, 400), (1, 400)),
+            long((0, 3), (2, 4)),
+        ];
+        assert!(lines.iter().all(|line| line.len > SHORT));
+        let brute = lines
+            .iter()
+            .flat_map(|line| line.points())
+            .chain(points)
+            .collect::<HashSet<_>>();
+        assert_eq!(distinct_points(&lines, &points), brute.len());
+    }
+}
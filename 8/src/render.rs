@@ -0,0 +1,166 @@
+//! Antenna maps with the antinodes drawn in, one colour per frequency.
+//!
+//! Antinodes show as `#` in the colour of their frequency, or bold white
+//! where several frequencies meet. Antennas keep their own colour, and sit on
+//! the colour of the antinode's frequency when they are also an antinode.
+//! Without colours the map reads like the ones in the puzzle description.
+
+use crate::antinode::Antinodes;
+use crate::grid::Grid;
+use crate::point::Point;
+use colored::{ColoredString, Colorize};
+use std::collections::HashMap;
+
+/// A colour for each frequency, spread evenly around the hue circle.
+pub struct Palette {
+    colours: HashMap<char, (u8, u8, u8)>,
+}
+
+impl Palette {
+    pub fn new(antinodes: &Antinodes) -> Self {
+        let count = antinodes.iter().count().max(1);
+        let colours = antinodes
+            .iter()
+            .enumerate()
+            .map(|(index, (frequency, _))| (frequency, hue(index as f64 * 360.0 / count as f64)))
+            .collect();
+        Self { colours }
+    }
+
+    pub fn paint(&self, text: &str, frequency: char) -> ColoredString {
+        let (r, g, b) = self
+            .colours
+            .get(&frequency)
+            .copied()
+            .unwrap_or((255, 255, 255));
+        text.truecolor(r, g, b)
+    }
+
+    fn paint_background(&self, text: &str, frequency: char) -> ColoredString {
+        let (r, g, b) = self
+            .colours
+            .get(&frequency)
+            .copied()
+            .unwrap_or((255, 255, 255));
+        text.black().on_truecolor(r, g, b)
+    }
+}
+
+/// The fully saturated colour at `degrees` on the hue circle.
+fn hue(degrees: f64) -> (u8, u8, u8) {
+    let sector = degrees / 60.0;
+    let rising = (255.0 * (1.0 - (sector % 2.0 - 1.0).abs())) as u8;
+    match sector as u32 {
+        0 => (255, rising, 0),
+        1 => (rising, 255, 0),
+        2 => (0, 255, rising),
+        3 => (0, rising, 255),
+        4 => (rising, 0, 255),
+        _ => (255, 0, rising),
+    }
+}
+
+pub fn render(grid: &Grid<char>, antinodes: &Antinodes) -> String {
+    let palette = Palette::new(antinodes);
+    let mut frequencies: HashMap<Point, Vec<char>> = HashMap::new();
+    for (frequency, points) in antinodes.iter() {
+        for &point in points {
+            frequencies.entry(point).or_default().push(frequency);
+        }
+    }
+
+    let mut result = String::new();
+    for row in 0..grid.height() as isize {
+        for col in 0..grid.width() as isize {
+            let cell = *grid.get(row, col);
+            let is_antenna = cell != '.' && cell != ' ';
+            let text = cell.to_string();
+            let painted = match (is_antenna, frequencies.get(&Point::new(row, col))) {
+                (false, None) => text.normal(),
+                (true, None) => palette.paint(&text, cell),
+                (false, Some(here)) if here.len() == 1 => palette.paint("#", here[0]),
+                (false, Some(_)) => "#".bright_white().bold(),
+                (true, Some(here)) => palette.paint_background(&text, here[0]),
+            };
+            result.push_str(&painted.to_string());
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// One line per frequency with its antennas, antinodes and how many of the
+/// antinodes are on an antenna, then the number of distinct antinodes.
+pub fn legend(grid: &Grid<char>, antinodes: &Antinodes) -> String {
+    let palette = Palette::new(antinodes);
+    let mut result = String::new();
+    for (frequency, points) in antinodes.iter() {
+        let antennas = grid
+            .cells()
+            .filter(|&(_, _, &cell)| cell == frequency)
+            .count();
+        let on_antennas = points
+            .iter()
+            .filter(|point| {
+                let cell = *grid.get(point.row, point.col);
+                cell != '.' && cell != ' '
+            })
+            .count();
+        result.push_str(&format!(
+            "{} {antennas:>3} antennas {:>4} antinodes ({on_antennas} on antennas)\n",
+            palette.paint(&frequency.to_string(), frequency),
+            points.len(),
+        ));
+    }
+    result.push_str(&format!("{} distinct antinodes\n", antinodes.count()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antinode::Resonance;
+    use crate::{find_antennas, parse_input};
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn test_plain_render() {
+        colored::control::set_override(false);
+        let grid = parse_input(EXAMPLE);
+        let antinodes = Resonance::part_1().antinodes(&find_antennas(&grid), grid.bounds());
+        assert_eq!(
+            render(&grid, &antinodes),
+            "\
+......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#.
+"
+        );
+        assert_eq!(
+            legend(&grid, &antinodes),
+            "\
+0   4 antennas   10 antinodes (1 on antennas)
+A   3 antennas    5 antinodes (0 on antennas)
+14 distinct antinodes
+"
+        );
+    }
+
+    #[test]
+    fn test_hues() {
+        assert_eq!(hue(0.0), (255, 0, 0));
+        assert_eq!(hue(120.0), (0, 255, 0));
+        assert_eq!(hue(240.0), (0, 0, 255));
+        assert_eq!(hue(60.0), (255, 255, 0));
+    }
+}
@@ -0,0 +1,34 @@
+//! Day 8 on antenna coordinates instead of a drawn map, see
+//! [`aoc_2024_8::coordinates`].
+//!
+//! `cargo run --release --bin sparse -- --input big.txt`, where `big.txt` can
+//! come from `cargo run --release --bin generate -- 1000000 1000000 2000`.
+
+use aoc_2024_8::antinode::Resonance;
+use aoc_2024_8::coordinates::parse_coordinates;
+use aoc_2024_lib::cli::Args;
+use std::io;
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+    let reporter = args.reporter(8);
+
+    for input in args.load("./coordinates.txt")? {
+        let (bounds, antennas) = parse_coordinates(&input.content).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {e}", input.name))
+        })?;
+
+        for (part, resonance) in [(1, Resonance::part_1()), (2, Resonance::part_2())] {
+            if args.runs_part(part) {
+                let record = reporter.time(part, &input.name, || {
+                    resonance.count(&antennas, bounds).distinct
+                });
+                reporter.emit(&record, |r| {
+                    format!("Result {part}: {}, {:?}", r.answer, r.duration())
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+//! The disk block by block, for following a compaction one move at a time.
+//!
+//! [`compress_observed`](crate::compress_observed) and
+//! [`compress_without_fragmentation_observed`](crate::compress_without_fragmentation_observed)
+//! report every [`Move`]. Applied in order to the [`BlockMap`] of the input,
+//! they lead to the compacted disk.
+
+use crate::DiskItem;
+use std::collections::HashMap;
+use std::fmt;
+
+/// `size` blocks of file `id` moved from block `from` onwards to block `to`
+/// onwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub size: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "file {}: {}..{} -> {}..{}",
+            self.id,
+            self.from,
+            self.from + self.size,
+            self.to,
+            self.to + self.size
+        )
+    }
+}
+
+/// The file id in each block, `None` for free ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMap(pub Vec<Option<usize>>);
+
+impl BlockMap {
+    pub fn new(items: &[DiskItem]) -> Self {
+        let blocks = items
+            .iter()
+            .flat_map(|item| match *item {
+                DiskItem::File(file) => std::iter::repeat_n(Some(file.id), file.size),
+                DiskItem::FreeSpace(size) => std::iter::repeat_n(None, size),
+            })
+            .collect();
+        Self(blocks)
+    }
+
+    /// Panics if the blocks moved from are not all of the file, or those
+    /// moved to are not all free.
+    pub fn apply(&mut self, step: Move) {
+        for offset in 0..step.size {
+            let (from, to) = (step.from + offset, step.to + offset);
+            assert_eq!(self.0[from], Some(step.id), "{step}: block {from}");
+            self.0[from] = None;
+            assert_eq!(self.0[to], None, "{step}: block {to}");
+            self.0[to] = Some(step.id);
+        }
+    }
+
+    /// The map as in the puzzle while every id is a single digit. Otherwise
+    /// each block is its id padded to the width of the largest one, or as
+    /// many dots, with a space between blocks.
+    pub fn render(&self) -> String {
+        let width = self
+            .0
+            .iter()
+            .flatten()
+            .max()
+            .map_or(1, |id| id.to_string().len());
+        let cell = |block: &Option<usize>| match block {
+            Some(id) => format!("{id:>width$}"),
+            None => ".".repeat(width),
+        };
+        let separator = if width == 1 { "" } else { " " };
+        self.0.iter().map(cell).collect::<Vec<_>>().join(separator)
+    }
+
+    pub fn fragmentation(&self) -> Fragmentation {
+        let mut fragmentation = Fragmentation::default();
+        let used = self
+            .0
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |last| last + 1);
+        let mut pieces: HashMap<usize, usize> = HashMap::new();
+        let mut free_run = 0;
+        for (index, block) in self.0.iter().enumerate() {
+            match block {
+                Some(id) => {
+                    if index == 0 || self.0[index - 1] != Some(*id) {
+                        *pieces.entry(*id).or_default() += 1;
+                    }
+                    free_run = 0;
+                }
+                None => {
+                    if index < used {
+                        fragmentation.holes += 1;
+                        if free_run == 0 {
+                            fragmentation.gaps += 1;
+                        }
+                    }
+                    free_run += 1;
+                    fragmentation.largest_free = fragmentation.largest_free.max(free_run);
+                }
+            }
+        }
+        fragmentation.split_files = pieces.values().filter(|&&count| count > 1).count();
+        fragmentation
+    }
+}
+
+/// How scattered the files and the free space on a disk are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fragmentation {
+    /// Files stored in more than one piece.
+    pub split_files: usize,
+    /// Runs of free blocks before the last used block.
+    pub gaps: usize,
+    /// Free blocks before the last used block.
+    pub holes: usize,
+    /// The longest run of free blocks, including the free space at the end.
+    pub largest_free: usize,
+}
+
+impl Fragmentation {
+    /// Split files plus gaps, the places reading the disk start to end leaves
+    /// a file or skips free space before the end of the files. 0 for a disk
+    /// with every file in one piece and all the free space at the end.
+    pub fn score(&self) -> usize {
+        self.split_files + self.gaps
+    }
+}
+
+impl fmt::Display for Fragmentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} split files, {} free blocks in {} gaps, largest free run {}",
+            self.split_files, self.holes, self.gaps, self.largest_free
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compress, compress_observed, compress_without_fragmentation,
+        compress_without_fragmentation_observed, parse_input, stringify_id_expansion,
+    };
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn test_replay_matches_compaction() {
+        let inputs = [
+            EXAMPLE,
+            "12345",
+            "012333133121414131401",
+            "2333133121414131402",
+        ];
+        for input in inputs {
+            let mut blocks = BlockMap::new(&parse_input(input).0);
+            let compressed = compress_observed(parse_input(input), |step| blocks.apply(step));
+            // The block level compaction leaves out the free space at the end.
+            blocks.0.truncate(BlockMap::new(&compressed).0.len());
+            assert_eq!(blocks, BlockMap::new(&compressed), "{input}");
+
+            let mut blocks = BlockMap::new(&parse_input(input).0);
+            let compressed = compress_without_fragmentation_observed(parse_input(input), |step| {
+                blocks.apply(step)
+            });
+            assert_eq!(blocks, BlockMap::new(&compressed), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_moves() {
+        let mut moves = Vec::new();
+        compress_without_fragmentation_observed(parse_input(EXAMPLE), |step| moves.push(step));
+        assert_eq!(
+            moves[..2],
+            [
+                Move {
+                    id: 9,
+                    from: 40,
+                    to: 2,
+                    size: 2
+                },
+                Move {
+                    id: 7,
+                    from: 32,
+                    to: 8,
+                    size: 3
+                },
+            ]
+        );
+        assert_eq!(moves.len(), 4);
+        assert_eq!(moves[0].to_string(), "file 9: 40..42 -> 2..4");
+
+        let mut moved = 0;
+        compress_observed(parse_input("12345"), |step| moved += step.size);
+        assert_eq!(moved, 5);
+    }
+
+    #[test]
+    fn test_render() {
+        let disk = parse_input(EXAMPLE);
+        assert_eq!(
+            BlockMap::new(&disk.0).render(),
+            stringify_id_expansion(&disk.0)
+        );
+
+        let disk = parse_input("101010101010101010102111");
+        assert_eq!(stringify_id_expansion(&disk.0), "01234567891010.11.");
+        assert_eq!(
+            BlockMap::new(&disk.0).render(),
+            " 0  1  2  3  4  5  6  7  8  9 10 10 .. 11 .."
+        );
+    }
+
+    #[test]
+    fn test_fragmentation() {
+        let before = BlockMap::new(&parse_input(EXAMPLE).0).fragmentation();
+        assert_eq!(
+            before,
+            Fragmentation {
+                split_files: 0,
+                gaps: 8,
+                holes: 14,
+                largest_free: 3
+            }
+        );
+
+        let after = BlockMap::new(&compress(parse_input(EXAMPLE))).fragmentation();
+        assert_eq!(after.split_files, 2);
+        assert_eq!(after.holes, 0);
+
+        let after = BlockMap::new(&compress_without_fragmentation(parse_input(EXAMPLE)));
+        assert_eq!(
+            after.fragmentation(),
+            Fragmentation {
+                split_files: 0,
+                gaps: 5,
+                holes: 12,
+                largest_free: 5
+            }
+        );
+        assert_eq!(
+            after.fragmentation().to_string(),
+            "0 split files, 12 free blocks in 5 gaps, largest free run 5"
+        );
+    }
+}
@@ -0,0 +1,448 @@
+//! Whole-file compaction with the free spans indexed by size.
+//!
+//! Free spans are kept in one min-heap of start positions per size, so a file
+//! finds the leftmost span it fits in by looking at the top of the heaps for
+//! its size and up, instead of scanning the disk from the start. Only sizes
+//! with a span get a heap, which for sizes from single digits is at most
+//! nine.
+//!
+//! A span a file leaves behind is never reused: every file still to move is
+//! to its left, and files only move left. Compacting until settled does reuse
+//! them, so it keeps the free spans by position as well, to merge a freed span
+//! with its neighbours.
+
+use crate::blocks::Move;
+use crate::{Compressed, Disk, DiskItem, File};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::fmt;
+
+/// Which of the free spans a file fits in it moves to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fit {
+    /// The leftmost one, as in the puzzle.
+    #[default]
+    Leftmost,
+    /// The smallest one, the leftmost of those on a tie.
+    Best,
+    /// The largest one, the leftmost of those on a tie.
+    Worst,
+}
+
+#[derive(Debug, Default)]
+pub struct FreeSpans {
+    /// Start positions of the spans of each size there is one of.
+    by_size: BTreeMap<usize, BinaryHeap<Reverse<usize>>>,
+}
+
+impl FreeSpans {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, start: usize, size: usize) {
+        if size == 0 {
+            return;
+        }
+        self.by_size.entry(size).or_default().push(Reverse(start));
+    }
+
+    /// Removes the span `fit` picks among those of at least `size` blocks
+    /// starting before `limit`, and returns its start and size.
+    pub fn take(&mut self, fit: Fit, size: usize, limit: usize) -> Option<(usize, usize)> {
+        // The leftmost span of each size, for the sizes with one before `limit`.
+        let mut candidates = self
+            .by_size
+            .range(size.max(1)..)
+            .filter_map(|(&fit, heap)| heap.peek().map(|&Reverse(start)| (fit, start)))
+            .filter(|&(_, start)| start < limit);
+        let (fit, start) = match fit {
+            Fit::Leftmost => candidates.min_by_key(|&(_, start)| start),
+            Fit::Best => candidates.next(),
+            Fit::Worst => candidates.next_back(),
+        }?;
+        let heap = self.by_size.get_mut(&fit).expect("a heap for the size");
+        heap.pop();
+        if heap.is_empty() {
+            self.by_size.remove(&fit);
+        }
+        Some((start, fit))
+    }
+}
+
+/// Free spans by position, each merged with any free neighbours, and their
+/// starts by size. Sizes from `largest` on share one set, as any of them fits
+/// every file.
+#[derive(Debug)]
+struct MergedSpans {
+    spans: BTreeMap<usize, usize>,
+    by_size: Vec<BTreeSet<usize>>,
+}
+
+impl MergedSpans {
+    fn new(largest: usize) -> Self {
+        Self {
+            spans: BTreeMap::new(),
+            by_size: vec![BTreeSet::new(); largest + 1],
+        }
+    }
+
+    fn bucket(&self, size: usize) -> usize {
+        size.min(self.by_size.len() - 1)
+    }
+
+    fn insert(&mut self, mut start: usize, mut size: usize) {
+        if size == 0 {
+            return;
+        }
+        if let Some((&before, &before_size)) = self.spans.range(..start).next_back() {
+            if before + before_size == start {
+                self.remove(before);
+                start = before;
+                size += before_size;
+            }
+        }
+        if let Some(after_size) = self.remove(start + size) {
+            size += after_size;
+        }
+        self.spans.insert(start, size);
+        let bucket = self.bucket(size);
+        self.by_size[bucket].insert(start);
+    }
+
+    fn remove(&mut self, start: usize) -> Option<usize> {
+        let size = self.spans.remove(&start)?;
+        let bucket = self.bucket(size);
+        self.by_size[bucket].remove(&start);
+        Some(size)
+    }
+
+    /// The start of the leftmost span of at least `size` blocks.
+    fn leftmost(&self, size: usize) -> Option<usize> {
+        self.by_size[self.bucket(size.max(1))..]
+            .iter()
+            .filter_map(|starts| starts.first())
+            .min()
+            .copied()
+    }
+}
+
+/// The files and free spans of a disk with their starts.
+struct Layout {
+    files: Vec<(usize, File)>,
+    spans: Vec<(usize, usize)>,
+    end: usize,
+}
+
+fn layout(disk: &Disk) -> Layout {
+    let mut files = Vec::new();
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for item in &disk.0 {
+        match *item {
+            DiskItem::File(file) => {
+                files.push((end, file));
+                end += file.size;
+            }
+            DiskItem::FreeSpace(size) => {
+                spans.push((end, size));
+                end += size;
+            }
+        }
+    }
+    Layout { files, spans, end }
+}
+
+/// The disk with `files` at their starts and free space everywhere else.
+fn place(mut files: Vec<(usize, File)>, end: usize) -> Compressed {
+    files.sort_by_key(|&(start, _)| start);
+    let mut compressed = Vec::with_capacity(files.len() * 2);
+    let mut position = 0;
+    for (start, file) in files {
+        if start > position {
+            compressed.push(DiskItem::FreeSpace(start - position));
+        }
+        compressed.push(DiskItem::File(file));
+        position = position.max(start + file.size);
+    }
+    if end > position {
+        compressed.push(DiskItem::FreeSpace(end - position));
+    }
+    compressed
+}
+
+/// Moves each file, from the right, to the free span before it that `fit`
+/// picks among those that fit the whole file, passing each move to `on_move`.
+pub fn compact_whole_files(disk: &Disk, fit: Fit, mut on_move: impl FnMut(Move)) -> Compressed {
+    let Layout {
+        mut files,
+        spans: free,
+        end,
+    } = layout(disk);
+    let mut spans = FreeSpans::new();
+    for (start, size) in free {
+        spans.insert(start, size);
+    }
+
+    for (start, file) in files.iter_mut().rev() {
+        if file.size == 0 {
+            continue;
+        }
+        if let Some((span, size)) = spans.take(fit, file.size, *start) {
+            on_move(Move {
+                id: file.id,
+                from: *start,
+                to: span,
+                size: file.size,
+            });
+            *start = span;
+            spans.insert(span + file.size, size - file.size);
+        }
+    }
+
+    place(files, end)
+}
+
+/// Makes the same moves as repeating [`compact_whole_files`] with leftmost
+/// fits until a pass moves nothing, so files also get to use the space others
+/// left behind. Free space either side of an empty file counts as one span.
+///
+/// A pass only visits the files that can move: the rightmost file before the
+/// last one visited whose size has a free span further left. A file moved in
+/// a pass waits for the next one, and the passes stop at one without moves.
+pub fn compact_until_settled(disk: &Disk, mut on_move: impl FnMut(Move)) -> Compressed {
+    let Layout {
+        mut files,
+        spans: free,
+        end,
+    } = layout(disk);
+    let largest = files.iter().map(|(_, file)| file.size).max().unwrap_or(0);
+    let mut spans = MergedSpans::new(largest);
+    for (start, size) in free {
+        spans.insert(start, size);
+    }
+    // Starts and indices in `files` of the files still to visit in this pass,
+    // by size.
+    let mut waiting = vec![BTreeSet::new(); largest + 1];
+    for (index, &(start, file)) in files.iter().enumerate() {
+        if file.size > 0 {
+            waiting[file.size].insert((start, index));
+        }
+    }
+
+    loop {
+        let mut moved = Vec::new();
+        let mut limit = end;
+        loop {
+            let next = (1..=largest)
+                .filter_map(|size| {
+                    let span = spans.leftmost(size).filter(|&span| span + 1 < limit)?;
+                    waiting[size]
+                        .range((span + 1, 0)..(limit, 0))
+                        .next_back()
+                        .copied()
+                })
+                .max();
+            let Some((start, index)) = next else {
+                break;
+            };
+            let file = files[index].1;
+            let span = spans.leftmost(file.size).expect("a span before the file");
+            let size = spans.remove(span).expect("a free span");
+            on_move(Move {
+                id: file.id,
+                from: start,
+                to: span,
+                size: file.size,
+            });
+            spans.insert(span + file.size, size - file.size);
+            spans.insert(start, file.size);
+            waiting[file.size].remove(&(start, index));
+            files[index].0 = span;
+            moved.push(index);
+            limit = start;
+        }
+        if moved.is_empty() {
+            return place(files, end);
+        }
+        for index in moved {
+            let (start, file) = files[index];
+            waiting[file.size].insert((start, index));
+        }
+    }
+}
+
+/// A file after one with a larger id, on a disk [`defragment`] cannot pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfOrder {
+    pub id: usize,
+    pub after: usize,
+}
+
+impl fmt::Display for OutOfOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "file {} comes after file {}", self.id, self.after)
+    }
+}
+
+impl std::error::Error for OutOfOrder {}
+
+/// Packs the files from the first block on in id order, each in one piece,
+/// with all the free space at the end.
+///
+/// The files have to be in id order already, as [`parse_input`] gives them.
+/// Otherwise packing a file could overwrite one that has not moved yet, and
+/// two files could have to swap places with no free block to do it through.
+///
+/// [`parse_input`]: crate::parse_input
+pub fn defragment(disk: &Disk, mut on_move: impl FnMut(Move)) -> Result<Compressed, OutOfOrder> {
+    let Layout { files, end, .. } = layout(disk);
+    if let Some(pair) = files.windows(2).find(|pair| pair[0].1.id > pair[1].1.id) {
+        return Err(OutOfOrder {
+            id: pair[1].1.id,
+            after: pair[0].1.id,
+        });
+    }
+
+    // A file only overlaps the blocks it moves to with its own, as every file
+    // before it in id order was packed in front of it.
+    let mut compressed = Vec::with_capacity(files.len() + 1);
+    let mut position = 0;
+    for (start, file) in files {
+        if start != position && file.size > 0 {
+            on_move(Move {
+                id: file.id,
+                from: start,
+                to: position,
+                size: file.size,
+            });
+        }
+        compressed.push(DiskItem::File(file));
+        position += file.size;
+    }
+    if end > position {
+        compressed.push(DiskItem::FreeSpace(end - position));
+    }
+    Ok(compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, stringify_id_expansion};
+
+    #[test]
+    fn test_take() {
+        let spans = || {
+            let mut spans = FreeSpans::new();
+            spans.insert(10, 3);
+            spans.insert(4, 1);
+            spans.insert(20, 5);
+            spans.insert(30, 2);
+            spans.insert(2, 0);
+            spans
+        };
+
+        let mut leftmost = spans();
+        assert_eq!(leftmost.take(Fit::Leftmost, 2, 100), Some((10, 3)));
+        assert_eq!(leftmost.take(Fit::Leftmost, 2, 15), None);
+        assert_eq!(leftmost.take(Fit::Leftmost, 1, 100), Some((4, 1)));
+        assert_eq!(leftmost.take(Fit::Leftmost, 6, 100), None);
+        assert_eq!(leftmost.take(Fit::Leftmost, 5, 100), Some((20, 5)));
+        assert_eq!(leftmost.take(Fit::Leftmost, 1, 100), Some((30, 2)));
+        assert_eq!(leftmost.take(Fit::Leftmost, 1, 100), None);
+
+        let mut best = spans();
+        assert_eq!(best.take(Fit::Best, 2, 100), Some((30, 2)));
+        assert_eq!(best.take(Fit::Best, 2, 100), Some((10, 3)));
+        assert_eq!(best.take(Fit::Best, 2, 15), None);
+
+        let mut worst = spans();
+        assert_eq!(worst.take(Fit::Worst, 1, 100), Some((20, 5)));
+        assert_eq!(worst.take(Fit::Worst, 1, 15), Some((10, 3)));
+        assert_eq!(worst.take(Fit::Worst, 1, 15), Some((4, 1)));
+
+        let mut huge = spans();
+        huge.insert(50, usize::MAX);
+        assert_eq!(huge.take(Fit::Worst, 1, 100), Some((50, usize::MAX)));
+        assert_eq!(huge.take(Fit::Worst, 1, 100), Some((20, 5)));
+    }
+
+    #[test]
+    fn test_huge_free_span() {
+        let disk = Disk(vec![
+            DiskItem::File(File { id: 0, size: 1 }),
+            DiskItem::FreeSpace(usize::MAX / 2),
+            DiskItem::File(File { id: 1, size: 2 }),
+        ]);
+        for fit in [Fit::Leftmost, Fit::Best, Fit::Worst] {
+            let mut moves = Vec::new();
+            compact_whole_files(&disk, fit, |step| moves.push(step));
+            assert_eq!(
+                moves,
+                [Move {
+                    id: 1,
+                    from: usize::MAX / 2 + 1,
+                    to: 1,
+                    size: 2
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_settles_like_repeated_passes() {
+        let mut seed = 9_u64;
+        for _ in 0..300 {
+            let mut next = || {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (seed >> 33) % 10
+            };
+            // Without empty files, which the passes took to split the free
+            // space around them.
+            let length = 1 + next() * 4;
+            let input = (0..length)
+                .map(|index| match next() {
+                    0 if index % 2 == 0 => 1,
+                    digit => digit,
+                })
+                .map(|digit| digit.to_string())
+                .collect::<String>();
+            let disk = parse_input(&input);
+
+            let (mut expected_moves, mut moves) = (Vec::new(), Vec::new());
+            let mut expected = disk.0.clone();
+            loop {
+                let before = expected_moves.len();
+                expected = compact_whole_files(&Disk(expected), Fit::Leftmost, |step| {
+                    expected_moves.push(step)
+                });
+                if expected_moves.len() == before {
+                    break;
+                }
+            }
+            let compressed = compact_until_settled(&disk, |step| moves.push(step));
+            assert_eq!(moves, expected_moves, "{input}");
+            assert_eq!(
+                stringify_id_expansion(&compressed),
+                stringify_id_expansion(&expected),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_defragment_out_of_order() {
+        let disk = Disk(vec![
+            DiskItem::File(File { id: 1, size: 2 }),
+            DiskItem::File(File { id: 0, size: 2 }),
+        ]);
+        let mut moves = Vec::new();
+        let error = defragment(&disk, |step| moves.push(step)).unwrap_err();
+        assert_eq!(error, OutOfOrder { id: 0, after: 1 });
+        assert_eq!(error.to_string(), "file 0 comes after file 1");
+        assert!(moves.is_empty());
+    }
+}
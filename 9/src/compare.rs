@@ -0,0 +1,35 @@
+//! Runs every compaction strategy on the same disk and lists the checksum,
+//! blocks moved and fragmentation of each: `cargo run --bin compare`.
+
+use aoc_2024_9::parse_input;
+use aoc_2024_9::strategy::Strategy;
+use aoc_2024_lib::cli::Args;
+use std::io;
+
+fn main() -> io::Result<()> {
+    let args = Args::from_env();
+
+    for input in args.load("./input.txt")? {
+        let disk = parse_input(&input.content);
+        println!("{}:", input.name);
+        println!(
+            "{:<12} {:>16} {:>8} {:>6}  fragmentation",
+            "strategy", "checksum", "moved", "score"
+        );
+        for strategy in Strategy::ALL {
+            let outcome = strategy
+                .run(&disk)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            println!(
+                "{:<12} {:>16} {:>8} {:>6}  {}",
+                strategy.to_string(),
+                outcome.checksum.to_string(),
+                outcome.moved,
+                outcome.fragmentation.score(),
+                outcome.fragmentation
+            );
+        }
+    }
+
+    Ok(())
+}
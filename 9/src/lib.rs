@@ -0,0 +1,383 @@
+use aoc_2024_lib::num::{Num, Overflow};
+
+pub mod blocks;
+pub mod compactor;
+pub mod strategy;
+
+use blocks::Move;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct File {
+    pub id: usize,
+    pub size: usize,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DiskItem {
+    File(File),
+    FreeSpace(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Disk(pub Vec<DiskItem>);
+
+impl IntoIterator for Disk {
+    type Item = DiskItem;
+    type IntoIter = std::vec::IntoIter<DiskItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<DiskItem> for Disk {
+    fn from_iter<I: IntoIterator<Item = DiskItem>>(iter: I) -> Self {
+        Disk(iter.into_iter().collect())
+    }
+}
+
+impl std::fmt::Display for Disk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", item)?;
+        }
+        write!(f, "]")
+    }
+}
+
+pub type Compressed = Vec<DiskItem>;
+
+pub fn stringify_id_expansion(disk: &[DiskItem]) -> String {
+    disk.iter()
+        .map(|item| match item {
+            DiskItem::File(file) => file.id.to_string().repeat(file.size),
+            DiskItem::FreeSpace(space) => ".".repeat(*space),
+        })
+        .collect()
+}
+
+pub fn parse_input(input: &str) -> Disk {
+    // File interweaved with free space.
+    input
+        .trim()
+        .chars()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            let size = c.to_digit(10)?;
+            if i % 2 == 0 {
+                Some(DiskItem::File(File {
+                    id: i / 2,
+                    size: size as usize,
+                }))
+            } else {
+                Some(DiskItem::FreeSpace(size as usize))
+            }
+        })
+        .collect()
+}
+
+pub fn compress(disk: Disk) -> Compressed {
+    compress_observed(disk, |_| {})
+}
+
+/// [`compress`], passing each run of blocks moved to `on_move`.
+pub fn compress_observed(disk: Disk, mut on_move: impl FnMut(Move)) -> Compressed {
+    let mut res = Vec::with_capacity(disk.0.len() / 2);
+
+    let disk = disk.0;
+    let starts = disk
+        .iter()
+        .scan(0, |position, item| {
+            let start = *position;
+            *position += match item {
+                DiskItem::File(file) => file.size,
+                DiskItem::FreeSpace(size) => *size,
+            };
+            Some(start)
+        })
+        .collect::<Vec<_>>();
+
+    let mut left = 0;
+    let mut right = disk.len() - 1;
+    // Blocks in `res`.
+    let mut position = 0;
+
+    let mut free_space_remaining = usize::MAX;
+    let mut post_split_file_size = usize::MAX;
+
+    while left < right {
+        match (&disk[left], &disk[right]) {
+            // If left is a file, just move to next position
+            (DiskItem::File(file), _) => {
+                free_space_remaining = usize::MAX;
+
+                res.push(disk[left]);
+                position += file.size;
+                left += 1;
+            }
+            // If right is free space, move left
+            (_, DiskItem::FreeSpace(_)) => {
+                right -= 1;
+            }
+            // If left is free space and right is file
+            (DiskItem::FreeSpace(free_space), DiskItem::File(file)) => {
+                let free_space = std::cmp::min(free_space_remaining, *free_space);
+                let file_size = std::cmp::min(post_split_file_size, file.size);
+                let moved = std::cmp::min(free_space, file_size);
+                if moved > 0 {
+                    // The blocks at the end of what is left of the file.
+                    on_move(Move {
+                        id: file.id,
+                        from: starts[right] + file_size - moved,
+                        to: position,
+                        size: moved,
+                    });
+                    position += moved;
+                }
+
+                if free_space >= file_size {
+                    post_split_file_size = usize::MAX;
+                    free_space_remaining = free_space - file_size;
+
+                    res.push(DiskItem::File(File {
+                        id: file.id,
+                        size: file_size,
+                    }));
+
+                    right -= 1;
+                } else {
+                    post_split_file_size = file_size - free_space;
+                    free_space_remaining = 0;
+
+                    res.push(DiskItem::File(File {
+                        id: file.id,
+                        size: free_space,
+                    }));
+
+                    left += 1;
+                }
+            }
+        }
+    }
+
+    if left == right {
+        if let DiskItem::File(file) = disk[left] {
+            res.push(DiskItem::File(File {
+                id: file.id,
+                size: std::cmp::min(post_split_file_size, file.size),
+            }));
+        }
+    }
+
+    res
+}
+
+/// Moves each file, from the right, to the leftmost free span it fits in,
+/// see [`compactor`].
+pub fn compress_without_fragmentation(disk: Disk) -> Compressed {
+    compress_without_fragmentation_observed(disk, |_| {})
+}
+
+/// [`compress_without_fragmentation`], passing each file moved to `on_move`.
+pub fn compress_without_fragmentation_observed(
+    disk: Disk,
+    on_move: impl FnMut(Move),
+) -> Compressed {
+    compactor::compact_whole_files(&disk, compactor::Fit::Leftmost, on_move)
+}
+
+pub fn calculate_checksum(compressed: &Compressed) -> Result<Num, Overflow> {
+    let mut sum = Num::zero();
+    let mut block_index = 0;
+    for item in compressed {
+        match item {
+            DiskItem::File(file) => {
+                let id = Num::try_from(file.id)?;
+                for _ in 0..file.size {
+                    sum = sum.add(&Num::try_from(block_index)?.mul(&id)?)?;
+                    block_index += 1;
+                }
+            }
+            DiskItem::FreeSpace(size) => {
+                block_index += size;
+            }
+        }
+    }
+    Ok(sum)
+}
+
+pub fn solve(input: &str) -> Result<Num, Overflow> {
+    let disk = parse_input(input);
+    let compressed = compress(disk);
+    calculate_checksum(&compressed)
+}
+
+pub fn solve_ex2(input: &str) -> Result<Num, Overflow> {
+    let disk = parse_input(input);
+    let compressed = compress_without_fragmentation(disk);
+    calculate_checksum(&compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    /// The original whole-file compactor, scanning from the left for every
+    /// file.
+    fn compress_without_fragmentation_by_scanning(disk: Disk) -> Compressed {
+        let mut res = disk.0.clone();
+
+        for r in (0..res.len()).rev() {
+            if let DiskItem::File(file) = res[r] {
+                for l in 0..r {
+                    if let DiskItem::FreeSpace(free_space) = res[l] {
+                        let remaining_free_space = free_space as isize - file.size as isize;
+                        if remaining_free_space >= 0 {
+                            res[r] = DiskItem::FreeSpace(file.size);
+                            res[l] = DiskItem::File(file);
+                            if remaining_free_space > 0 {
+                                res.insert(
+                                    l + 1,
+                                    DiskItem::FreeSpace(remaining_free_space as usize),
+                                );
+                            }
+
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    #[test]
+    fn parse_trivial() {
+        assert_eq!(
+            parse_input("12345"),
+            Disk(vec![
+                DiskItem::File(File { id: 0, size: 1 }),
+                DiskItem::FreeSpace(2),
+                DiskItem::File(File { id: 1, size: 3 }),
+                DiskItem::FreeSpace(4),
+                DiskItem::File(File { id: 2, size: 5 }),
+            ])
+        );
+    }
+
+    #[test]
+    fn compress_trivial() {
+        assert_eq!(
+            compress(parse_input("12345")),
+            vec![
+                DiskItem::File(File { id: 0, size: 1 }),
+                DiskItem::File(File { id: 2, size: 2 }),
+                DiskItem::File(File { id: 1, size: 3 }),
+                DiskItem::File(File { id: 2, size: 3 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn stringify_id_expansion_cases() {
+        assert_eq!(
+            stringify_id_expansion(&parse_input("12345").0),
+            "0..111....22222"
+        );
+
+        assert_eq!(
+            stringify_id_expansion(&parse_input(EXAMPLE).0),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+    }
+
+    #[test]
+    fn stringify_id_expansion_example() {
+        let parsed = parse_input(EXAMPLE);
+        let compressed = compress(parsed);
+        assert_eq!(
+            stringify_id_expansion(&compressed),
+            "0099811188827773336446555566"
+        );
+    }
+
+    #[test]
+    fn test_example() {
+        let x = parse_input(EXAMPLE);
+        println!("{}", stringify_id_expansion(&x.0));
+        let y = compress(x);
+        println!("{:?}", y);
+        println!("{}", stringify_id_expansion(&y));
+
+        assert_eq!(solve(EXAMPLE), Ok(Num::from(1928)));
+    }
+
+    #[test]
+    fn test_example_expanded() {
+        let input = "012333133121414131401";
+        let parsed = parse_input(input);
+        println!("{}", stringify_id_expansion(&parsed.0));
+        let compressed = compress(parsed);
+        println!("{}", stringify_id_expansion(&compressed));
+
+        assert_eq!(solve(input), Ok(Num::from(2029)));
+    }
+
+    #[test]
+    fn compress_without_fragmentation_ex2() {
+        let compressed = compress_without_fragmentation(parse_input(EXAMPLE));
+        assert_eq!(
+            stringify_id_expansion(&compressed),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+    }
+
+    #[test]
+    fn compress_without_fragmentation_matches_scanning() {
+        let mut seed = 9_u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        for _ in 0..300 {
+            let length = 1 + next(60);
+            let input = (0..length)
+                .map(|_| char::from_digit(next(10) as u32, 10).unwrap())
+                .collect::<String>();
+            let expected = compress_without_fragmentation_by_scanning(parse_input(&input));
+            let compressed = compress_without_fragmentation(parse_input(&input));
+            assert_eq!(
+                stringify_id_expansion(&compressed),
+                stringify_id_expansion(&expected),
+                "{input}"
+            );
+            assert_eq!(
+                calculate_checksum(&compressed),
+                calculate_checksum(&expected),
+                "{input}"
+            );
+        }
+        for input in [EXAMPLE, "12345", "012333133121414131401"] {
+            assert_eq!(
+                stringify_id_expansion(&compress_without_fragmentation(parse_input(input))),
+                stringify_id_expansion(&compress_without_fragmentation_by_scanning(parse_input(
+                    input
+                )))
+            );
+        }
+    }
+
+    #[test]
+    fn test_example_ex2() {
+        assert_eq!(solve_ex2(EXAMPLE), Ok(Num::from(2858)));
+    }
+}
@@ -0,0 +1,50 @@
+//! Replays the compaction one move at a time, printing each move and the
+//! block map after it, with the fragmentation before and after:
+//! `cargo run --bin replay -- --input example.txt --part 2`.
+//!
+//! Maps wider than [`MAX_MAP_BLOCKS`] are left out, only the moves are listed.
+
+use aoc_2024_9::blocks::BlockMap;
+use aoc_2024_9::{compress_observed, compress_without_fragmentation_observed, parse_input};
+use aoc_2024_lib::cli::Args;
+
+const MAX_MAP_BLOCKS: usize = 200;
+
+fn main() -> std::io::Result<()> {
+    let args = Args::from_env();
+
+    for input in args.load("./input.txt")? {
+        for part in [1, 2].into_iter().filter(|&part| args.runs_part(part)) {
+            let disk = parse_input(&input.content);
+            let mut blocks = BlockMap::new(&disk.0);
+            let show_maps = blocks.0.len() <= MAX_MAP_BLOCKS;
+            let before = blocks.fragmentation();
+
+            println!("{} part {part}:", input.name);
+            if show_maps {
+                println!("{}", blocks.render());
+            }
+            let mut moves = 0;
+            let mut moved = 0;
+            let on_move = |step| {
+                blocks.apply(step);
+                moves += 1;
+                moved += step.size;
+                println!("{step}");
+                if show_maps {
+                    println!("{}", blocks.render());
+                }
+            };
+            match part {
+                1 => compress_observed(disk, on_move),
+                _ => compress_without_fragmentation_observed(disk, on_move),
+            };
+
+            println!("{moves} moves, {moved} blocks moved");
+            println!("before: {before}");
+            println!("after:  {}\n", blocks.fragmentation());
+        }
+    }
+
+    Ok(())
+}
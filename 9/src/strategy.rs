@@ -0,0 +1,248 @@
+//! The ways of compacting a disk, side by side.
+//!
+//! Every [`Strategy`] runs on the same [`Disk`] and reports its checksum, the
+//! blocks it moved and how fragmented it left the disk, so they can be
+//! compared.
+
+use crate::blocks::{BlockMap, Fragmentation, Move};
+use crate::compactor::{compact_until_settled, compact_whole_files, defragment, Fit, OutOfOrder};
+use crate::{calculate_checksum, compress_observed, Compressed, Disk};
+use aoc_2024_lib::num::{Num, Overflow};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Block by block from the end into the first free blocks, part 1.
+    Blocks,
+    /// Whole files from the end into the leftmost span they fit in, part 2.
+    FirstFit,
+    /// Whole files from the end into the smallest span they fit in.
+    BestFit,
+    /// Whole files from the end into the largest span they fit in.
+    WorstFit,
+    /// First fit passes until no file can move further left.
+    MultiPass,
+    /// Every file in one piece, packed in id order.
+    Defragment,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 6] = [
+        Strategy::Blocks,
+        Strategy::FirstFit,
+        Strategy::BestFit,
+        Strategy::WorstFit,
+        Strategy::MultiPass,
+        Strategy::Defragment,
+    ];
+
+    /// Only [`Strategy::Defragment`] fails, on files out of id order.
+    pub fn compact(
+        &self,
+        disk: &Disk,
+        on_move: impl FnMut(Move),
+    ) -> Result<Compressed, OutOfOrder> {
+        Ok(match self {
+            Strategy::Blocks => compress_observed(Disk(disk.0.clone()), on_move),
+            Strategy::FirstFit => compact_whole_files(disk, Fit::Leftmost, on_move),
+            Strategy::BestFit => compact_whole_files(disk, Fit::Best, on_move),
+            Strategy::WorstFit => compact_whole_files(disk, Fit::Worst, on_move),
+            Strategy::MultiPass => compact_until_settled(disk, on_move),
+            Strategy::Defragment => defragment(disk, on_move)?,
+        })
+    }
+
+    pub fn run(&self, disk: &Disk) -> Result<Outcome, Error> {
+        let mut blocks = BlockMap::new(&disk.0);
+        let mut moved = 0;
+        let compressed = self.compact(disk, |step| {
+            moved += step.size;
+            blocks.apply(step);
+        })?;
+        Ok(Outcome {
+            checksum: calculate_checksum(&compressed)?,
+            moved,
+            fragmentation: blocks.fragmentation(),
+            compressed,
+        })
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Strategy::Blocks => "blocks",
+            Strategy::FirstFit => "first-fit",
+            Strategy::BestFit => "best-fit",
+            Strategy::WorstFit => "worst-fit",
+            Strategy::MultiPass => "multi-pass",
+            Strategy::Defragment => "defragment",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Overflow(Overflow),
+    OutOfOrder(OutOfOrder),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Overflow(error) => error.fmt(f),
+            Error::OutOfOrder(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Overflow(error) => Some(error),
+            Error::OutOfOrder(error) => Some(error),
+        }
+    }
+}
+
+impl From<Overflow> for Error {
+    fn from(error: Overflow) -> Self {
+        Error::Overflow(error)
+    }
+}
+
+impl From<OutOfOrder> for Error {
+    fn from(error: OutOfOrder) -> Self {
+        Error::OutOfOrder(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub compressed: Compressed,
+    pub checksum: Num,
+    /// Blocks moved, counting a block again each time it moves.
+    pub moved: usize,
+    /// Of the whole disk, including the free space at the end.
+    pub fragmentation: Fragmentation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, stringify_id_expansion};
+
+    static EXAMPLE: &str = include_str!("../example.txt");
+
+    fn run(strategy: Strategy, input: &str) -> Outcome {
+        strategy.run(&parse_input(input)).unwrap()
+    }
+
+    #[test]
+    fn test_puzzle_strategies() {
+        assert_eq!(run(Strategy::Blocks, EXAMPLE).checksum, Num::from(1928));
+        assert_eq!(run(Strategy::FirstFit, EXAMPLE).checksum, Num::from(2858));
+    }
+
+    #[test]
+    fn test_example() {
+        let expected = [
+            ("0099811188827773336446555566", 12, 2),
+            ("00992111777.44.333....5555.6666.....8888..", 8, 5),
+            ("00992111777.44.333....5555.6666.....8888..", 8, 5),
+            ("00992111777.44.333....5555.6666.....8888..", 8, 5),
+            // 8888 fits where 44 was once 44 has moved.
+            ("00992111777.44.33388885555.6666...........", 12, 3),
+            ("0011123334455556666777888899..............", 26, 0),
+        ];
+        for (strategy, expected) in Strategy::ALL.into_iter().zip(expected) {
+            let outcome = run(strategy, EXAMPLE);
+            assert_eq!(
+                (
+                    stringify_id_expansion(&outcome.compressed).as_str(),
+                    outcome.moved,
+                    outcome.fragmentation.score()
+                ),
+                expected,
+                "{strategy}"
+            );
+        }
+        assert_eq!(run(Strategy::MultiPass, EXAMPLE).checksum, Num::from(2282));
+    }
+
+    #[test]
+    fn test_fits() {
+        // Gaps of 2, 3 and 1 blocks before file 3.
+        let disk = parse_input("1213111");
+        let first_move = |strategy: Strategy| {
+            let mut moves = Vec::new();
+            strategy.compact(&disk, |step| moves.push(step)).unwrap();
+            moves[0]
+        };
+        assert_eq!(first_move(Strategy::FirstFit).to, 1);
+        assert_eq!(first_move(Strategy::BestFit).to, 8);
+        assert_eq!(first_move(Strategy::WorstFit).to, 4);
+    }
+
+    #[test]
+    fn test_multi_pass_until_settled() {
+        // 222 only fits once 1 has moved out of the way.
+        assert_eq!(
+            stringify_id_expansion(&run(Strategy::FirstFit, "2112310").compressed),
+            "001...222."
+        );
+        let outcome = run(Strategy::MultiPass, "2112310");
+        assert_eq!(stringify_id_expansion(&outcome.compressed), "001222....");
+        assert_eq!(outcome.moved, 4);
+        assert_eq!(outcome.fragmentation.score(), 0);
+    }
+
+    #[test]
+    fn test_compacted_disk() {
+        // The files of a compacted disk are no longer in id order, which
+        // only defragmenting needs.
+        let disk = Disk(run(Strategy::FirstFit, EXAMPLE).compressed);
+        for strategy in Strategy::ALL {
+            let outcome = strategy.run(&disk);
+            if strategy == Strategy::Defragment {
+                assert_eq!(
+                    outcome,
+                    Err(Error::OutOfOrder(OutOfOrder { id: 2, after: 9 }))
+                );
+            } else {
+                assert!(outcome.is_ok(), "{strategy}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_strategies_agree_with_replay() {
+        let mut seed = 50_u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        for _ in 0..100 {
+            let input = (0..1 + next(40))
+                .map(|_| char::from_digit(next(10) as u32, 10).unwrap())
+                .collect::<String>();
+            let disk = parse_input(&input);
+            for strategy in Strategy::ALL {
+                // `run` replays the moves, which panics if one is not possible.
+                let outcome = strategy.run(&disk).unwrap();
+                let mut blocks = BlockMap::new(&outcome.compressed);
+                blocks.0.resize(BlockMap::new(&disk.0).0.len(), None);
+                assert_eq!(
+                    blocks.fragmentation(),
+                    outcome.fragmentation,
+                    "{strategy} {input}"
+                );
+                if strategy != Strategy::Blocks {
+                    assert_eq!(blocks.fragmentation().split_files, 0, "{strategy} {input}");
+                }
+            }
+        }
+    }
+}
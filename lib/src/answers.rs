@@ -0,0 +1,258 @@
+//! Local store of confirmed answers, so refactors cannot silently change results.
+//!
+//! The store is the `answers.json` in the working directory, keyed by day,
+//! input name and part, so days run from the same directory on inputs of the
+//! same name keep their answers apart:
+//!
+//! ```json
+//! { "7": { "input.txt": { "1": { "correct": "3749", "wrong": ["3748"] } } } }
+//! ```
+//!
+//! The file is only ever read and written locally.
+
+use crate::report::Record;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PATH: &str = "./answers.json";
+
+/// What to do with the answers of the current run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Action {
+    /// Compare against the store, failing on a known-bad answer.
+    #[default]
+    Verify,
+    /// Store the answers as confirmed correct.
+    Confirm,
+    /// Store the answers as known to be wrong.
+    Reject,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wrong: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Unknown,
+    Correct,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    pub expected: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} part {} ({}): answer {} ",
+            self.day, self.part, self.input, self.answer
+        )?;
+        match &self.expected {
+            Some(expected) => write!(f, "does not match the confirmed answer {expected}"),
+            None => write!(f, "is known to be wrong"),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnswerStore {
+    path: PathBuf,
+    days: BTreeMap<u8, BTreeMap<String, BTreeMap<u8, Answers>>>,
+}
+
+impl AnswerStore {
+    /// Loads the store, starting empty if the file does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let days = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("failed to parse {}: {e}", path.display()),
+                )
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, days })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.days).map_err(io::Error::other)?;
+        std::fs::write(&self.path, json + "\n")
+    }
+
+    pub fn get(&self, day: u8, input: &str, part: u8) -> Option<&Answers> {
+        self.days.get(&day)?.get(input)?.get(&part)
+    }
+
+    pub fn verify(&self, record: &Record) -> Result<Verdict, Mismatch> {
+        let Some(answers) = self.get(record.day, &record.input, record.part) else {
+            return Ok(Verdict::Unknown);
+        };
+        let mismatch = |expected: Option<&String>| Mismatch {
+            day: record.day,
+            part: record.part,
+            input: record.input.clone(),
+            answer: record.answer.clone(),
+            expected: expected.cloned(),
+        };
+
+        match &answers.correct {
+            Some(correct) if *correct == record.answer => Ok(Verdict::Correct),
+            Some(correct) => Err(mismatch(Some(correct))),
+            None if answers.wrong.contains(&record.answer) => Err(mismatch(None)),
+            None => Ok(Verdict::Unknown),
+        }
+    }
+
+    pub fn confirm(&mut self, record: &Record) {
+        let answers = self.entry(record);
+        answers.wrong.retain(|wrong| *wrong != record.answer);
+        answers.correct = Some(record.answer.clone());
+    }
+
+    pub fn reject(&mut self, record: &Record) {
+        let answers = self.entry(record);
+        if answers.correct.as_ref() == Some(&record.answer) {
+            answers.correct = None;
+        }
+        if !answers.wrong.contains(&record.answer) {
+            answers.wrong.push(record.answer.clone());
+        }
+    }
+
+    /// Applies `action` to the record, saving the store when it changed.
+    pub fn apply(
+        &mut self,
+        action: Action,
+        record: &Record,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match action {
+            Action::Verify => {
+                self.verify(record)?;
+                return Ok(());
+            }
+            Action::Confirm => self.confirm(record),
+            Action::Reject => self.reject(record),
+        }
+        Ok(self.save()?)
+    }
+
+    fn entry(&mut self, record: &Record) -> &mut Answers {
+        self.days
+            .entry(record.day)
+            .or_default()
+            .entry(record.input.clone())
+            .or_default()
+            .entry(record.part)
+            .or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Format, Reporter};
+    use std::time::Duration;
+
+    fn record(part: u8, answer: &str) -> Record {
+        day_record(7, part, answer)
+    }
+
+    fn day_record(day: u8, part: u8, answer: &str) -> Record {
+        Reporter::new(day, Format::Text).record(part, "input.txt", answer, Duration::ZERO)
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut store = AnswerStore::default();
+        assert_eq!(store.verify(&record(1, "3748")), Ok(Verdict::Unknown));
+
+        store.reject(&record(1, "3748"));
+        let err = store.verify(&record(1, "3748")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 7 part 1 (input.txt): answer 3748 is known to be wrong"
+        );
+        assert_eq!(store.verify(&record(1, "3749")), Ok(Verdict::Unknown));
+
+        store.confirm(&record(1, "3749"));
+        assert_eq!(store.verify(&record(1, "3749")), Ok(Verdict::Correct));
+        let err = store.verify(&record(1, "1")).unwrap_err();
+        assert_eq!(err.expected.as_deref(), Some("3749"));
+        assert_eq!(store.verify(&record(2, "1")), Ok(Verdict::Unknown));
+    }
+
+    #[test]
+    fn test_confirm_and_reject_replace_each_other() {
+        let mut store = AnswerStore::default();
+        store.reject(&record(2, "11387"));
+        store.confirm(&record(2, "11387"));
+        assert_eq!(
+            store.get(7, "input.txt", 2),
+            Some(&Answers {
+                correct: Some("11387".to_string()),
+                wrong: vec![],
+            })
+        );
+
+        store.reject(&record(2, "11387"));
+        assert_eq!(store.get(7, "input.txt", 2).unwrap().correct, None);
+    }
+
+    #[test]
+    fn test_days_do_not_collide() {
+        let mut store = AnswerStore::default();
+        store.confirm(&day_record(2, 1, "686"));
+        store.reject(&day_record(7, 1, "686"));
+
+        assert_eq!(store.verify(&day_record(2, 1, "686")), Ok(Verdict::Correct));
+        assert!(store.verify(&day_record(7, 1, "686")).is_err());
+        assert_eq!(
+            store.verify(&day_record(7, 1, "3749")),
+            Ok(Verdict::Unknown)
+        );
+
+        store.confirm(&day_record(7, 1, "3749"));
+        assert_eq!(
+            store.get(2, "input.txt", 1).unwrap().correct.as_deref(),
+            Some("686")
+        );
+        assert_eq!(
+            store.verify(&day_record(7, 1, "3749")),
+            Ok(Verdict::Correct)
+        );
+    }
+
+    #[test]
+    fn test_save_and_open() {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}.json", std::process::id()));
+        let mut store = AnswerStore::open(&path).expect("Missing file should open empty");
+        store.confirm(&record(1, "3749"));
+        store.reject(&record(1, "3748"));
+        store.save().unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(r#""correct": "3749""#));
+        assert!(content.contains(r#""7": {"#));
+        assert_eq!(AnswerStore::open(&path).unwrap(), store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+//! Command-line arguments shared by all day binaries.
+//!
+//! ```text
+//! --input PATH     read PATH instead of the day's default input file
+//! --section NAME   only run the `# NAME` section of a markdown inputs file
+//! --stdin          read the input from standard input
+//! --part 1|2       only run one part (a bare `1` or `2` works too)
+//! --format FORMAT  `text` (default) or `json`
+//! --record         store this run's answers as confirmed in `answers.json`
+//! --reject         store this run's answers as known to be wrong
+//! ```
+//!
+//! Without `--record`/`--reject`, answers are verified against
+//! `answers.json` and a mismatch ends the run with an error.
+//!
+//! Files ending in `.md` (and stdin starting with `# `) are parsed as
+//! markdown inputs files with one section per input, anything else is a
+//! single input named after the file.
+
+use crate::answers::{self, Action, AnswerStore};
+use crate::input_reader::{parse_inputs_file, Input};
+use crate::report::{Format, Reporter};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "\
+Usage: [--input PATH | --stdin] [--section NAME] [--part 1|2] [--format text|json]
+       [--record | --reject]";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    pub input: Option<PathBuf>,
+    pub section: Option<String>,
+    pub stdin: bool,
+    pub part: Option<u8>,
+    pub format: Format,
+    pub answers: Action,
+}
+
+impl Args {
+    /// Parses the process arguments, exiting with the usage on errors.
+    /// Also sets up tracing, see [`crate::trace`].
+    pub fn from_env() -> Self {
+        crate::trace::init();
+        Self::parse(std::env::args().skip(1)).unwrap_or_else(|e| {
+            eprintln!("{e}\n{USAGE}");
+            std::process::exit(2);
+        })
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for {flag}"))
+            };
+
+            match flag {
+                "--input" => parsed.input = Some(PathBuf::from(value()?)),
+                "--section" => parsed.section = Some(value()?),
+                "--stdin" => parsed.stdin = true,
+                "--part" => parsed.part = Some(parse_part(&value()?)?),
+                "--format" => parsed.format = value()?.parse()?,
+                "--record" | "--reject" if parsed.answers != Action::Verify => {
+                    return Err("--record and --reject are mutually exclusive".to_string())
+                }
+                "--record" => parsed.answers = Action::Confirm,
+                "--reject" => parsed.answers = Action::Reject,
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                part if !part.starts_with('-') => parsed.part = Some(parse_part(part)?),
+                _ => return Err(format!("unknown argument `{arg}`")),
+            }
+        }
+
+        if parsed.stdin && parsed.input.is_some() {
+            return Err("--input and --stdin are mutually exclusive".to_string());
+        }
+
+        Ok(parsed)
+    }
+
+    /// Reporter for the day, backed by the answer store in the working
+    /// directory. Exits if the store cannot be read.
+    pub fn reporter(&self, day: u8) -> Reporter {
+        let store = AnswerStore::open(answers::DEFAULT_PATH).unwrap_or_else(|e| {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        });
+        Reporter::new(day, self.format).with_answers(store, self.answers)
+    }
+
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|selected| selected == part)
+    }
+
+    /// Reads the selected inputs, falling back to `default_path` relative to
+    /// the working directory.
+    pub fn load(&self, default_path: &str) -> io::Result<Vec<Input>> {
+        let inputs = if self.stdin {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            if content.trim_start().starts_with("# ") {
+                parse_inputs_file(&content)?.sections
+            } else {
+                vec![Input {
+                    name: "stdin".to_string(),
+                    content,
+                }]
+            }
+        } else {
+            let path = self
+                .input
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(default_path));
+            read_path(&path)?
+        };
+
+        match &self.section {
+            Some(section) => select(inputs, section),
+            None => Ok(inputs),
+        }
+    }
+
+    /// The selected input as a stream, with its name, falling back to
+    /// `default_path` relative to the working directory. `--section` needs
+    /// the whole file, so it is an error here.
+    pub fn open(&self, default_path: &str) -> io::Result<(String, Box<dyn BufRead>)> {
+        if self.section.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "--section is not supported when streaming",
+            ));
+        }
+        if self.stdin {
+            return Ok(("stdin".to_string(), Box::new(io::stdin().lock())));
+        }
+
+        let path = self
+            .input
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(default_path));
+        let file = File::open(&path).map_err(|e| {
+            io::Error::new(e.kind(), format!("failed to open {}: {e}", path.display()))
+        })?;
+        Ok((name_of(&path), Box::new(BufReader::new(file))))
+    }
+
+    /// Like [`Args::load`], but without `--section` a file with several
+    /// sections is narrowed down to `default_section`.
+    pub fn load_section(
+        &self,
+        default_path: &str,
+        default_section: &str,
+    ) -> io::Result<Vec<Input>> {
+        let inputs = self.load(default_path)?;
+        if self.section.is_none() && inputs.len() > 1 {
+            return select(inputs, default_section);
+        }
+        Ok(inputs)
+    }
+}
+
+fn select(inputs: Vec<Input>, section: &str) -> io::Result<Vec<Input>> {
+    let selected = inputs
+        .into_iter()
+        .filter(|input| input.name.to_lowercase() == section.to_lowercase())
+        .collect::<Vec<_>>();
+
+    if selected.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Input with name {section} not found"),
+        ));
+    }
+    Ok(selected)
+}
+
+fn parse_part(value: &str) -> Result<u8, String> {
+    match value {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("invalid part `{value}`, expected 1 or 2")),
+    }
+}
+
+fn read_path(path: &Path) -> io::Result<Vec<Input>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("failed to read {}: {e}", path.display())))?;
+
+    if path.extension().is_some_and(|extension| extension == "md") {
+        return Ok(parse_inputs_file(&content)?.sections);
+    }
+
+    Ok(vec![Input {
+        name: name_of(path),
+        content,
+    }])
+}
+
+fn name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse(&[]), Ok(Args::default()));
+        assert_eq!(
+            parse(&["--input", "big.txt", "--part", "2", "--format=json"]),
+            Ok(Args {
+                input: Some(PathBuf::from("big.txt")),
+                part: Some(2),
+                format: Format::Json,
+                ..Args::default()
+            })
+        );
+        assert_eq!(
+            parse(&["1", "--section=Example", "--stdin"]),
+            Ok(Args {
+                section: Some("Example".to_string()),
+                stdin: true,
+                part: Some(1),
+                ..Args::default()
+            })
+        );
+        assert_eq!(parse(&["--reject"]).unwrap().answers, Action::Reject);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse(&["--part", "3"]).is_err());
+        assert!(parse(&["--input"]).is_err());
+        assert!(parse(&["--format", "yaml"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["--stdin", "--input", "input.txt"]).is_err());
+        assert!(parse(&["--record", "--reject"]).is_err());
+    }
+
+    #[test]
+    fn test_runs_part() {
+        let both = Args::default();
+        assert!(both.runs_part(1) && both.runs_part(2));
+
+        let second = parse(&["--part", "2"]).unwrap();
+        assert!(!second.runs_part(1) && second.runs_part(2));
+    }
+
+    #[test]
+    fn test_load_markdown_sections() {
+        let args = parse(&["--input", "inputs.md", "--section", "simple"]).unwrap();
+        let inputs = args.load("input.txt").expect("Should read inputs.md");
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].name, "Simple");
+
+        let args = parse(&["--section", "Missing"]).unwrap();
+        assert!(args.load("inputs.md").is_err());
+    }
+
+    #[test]
+    fn test_load_default_section() {
+        let inputs = Args::default()
+            .load_section("inputs.md", "Input")
+            .expect("Should read inputs.md");
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].name, "Input");
+
+        let args = parse(&["--section", "Trivial"]).unwrap();
+        let inputs = args.load_section("inputs.md", "Input").unwrap();
+        assert_eq!(inputs[0].name, "Trivial");
+    }
+
+    #[test]
+    fn test_load_plain_file() {
+        let args = parse(&["--input", "Cargo.toml"]).unwrap();
+        let inputs = args.load("input.txt").expect("Should read Cargo.toml");
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].name, "Cargo.toml");
+        assert!(inputs[0].content.contains("aoc-2024-lib"));
+    }
+
+    #[test]
+    fn test_open() {
+        let args = parse(&["--input", "Cargo.toml"]).unwrap();
+        let (name, reader) = args.open("input.txt").expect("Should open Cargo.toml");
+        assert_eq!(name, "Cargo.toml");
+        let first = reader.lines().next().unwrap().unwrap();
+        assert_eq!(first, "[package]");
+
+        let args = parse(&["--input", "inputs.md", "--section", "Input"]).unwrap();
+        let error = args.open("input.txt").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(parse(&["--input", "missing.txt"])
+            .unwrap()
+            .open("")
+            .is_err());
+    }
+}
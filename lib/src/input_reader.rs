@@ -62,7 +62,11 @@ fn parse_markdown_subset(input: &str) -> IResult<&str, InputsFile> {
 
 pub fn read_input(file_path: &str) -> Result<InputsFile, std::io::Error> {
     let contents = fs::read_to_string(file_path)?;
-    match parse_markdown_subset(&contents) {
+    parse_inputs_file(&contents)
+}
+
+pub fn parse_inputs_file(contents: &str) -> Result<InputsFile, std::io::Error> {
+    match parse_markdown_subset(contents) {
         Ok((_, input_file)) => Ok(input_file),
         Err(e) => Err(std::io::Error::new(
             std::io::ErrorKind::InvalidData,
@@ -1,3 +1,11 @@
+pub mod answers;
 pub mod board;
+pub mod cli;
 pub mod input_reader;
+pub mod num;
 pub mod point2;
+pub mod report;
+pub mod rng;
+pub mod trace;
+
+pub use tracing;
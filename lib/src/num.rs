@@ -0,0 +1,281 @@
+//! Integer type shared by the solvers whose answers can outgrow 64 bits.
+//!
+//! The representation is picked at compile time:
+//! - default: `i128` with plain operators, wide enough for anything that fit
+//!   the `u64`/`usize` values of the original code,
+//! - `checked` feature: `i128` where every overflow is reported as an [`Overflow`] error,
+//! - `bigint` feature: arbitrary-precision integers, never overflows.
+//!
+//! All operations return [`NumResult`] regardless of the mode, so solver code
+//! does not change when the feature is switched.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(not(feature = "bigint"))]
+type Repr = i128;
+
+#[cfg(feature = "bigint")]
+type Repr = num_bigint::BigInt;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Num(Repr);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub op: &'static str,
+    pub lhs: String,
+    pub rhs: String,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "integer overflow in `{} {} {}`",
+            self.lhs, self.op, self.rhs
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+pub type NumResult = Result<Num, Overflow>;
+
+macro_rules! binary_op {
+    ($name:ident, $checked:ident, $op:tt) => {
+        #[cfg(not(any(feature = "checked", feature = "bigint")))]
+        pub fn $name(&self, rhs: &Num) -> NumResult {
+            Ok(Num(self.0 $op rhs.0))
+        }
+
+        #[cfg(all(feature = "checked", not(feature = "bigint")))]
+        pub fn $name(&self, rhs: &Num) -> NumResult {
+            self.0.$checked(rhs.0).map(Num).ok_or_else(|| Overflow {
+                op: stringify!($op),
+                lhs: self.to_string(),
+                rhs: rhs.to_string(),
+            })
+        }
+
+        #[cfg(feature = "bigint")]
+        pub fn $name(&self, rhs: &Num) -> NumResult {
+            Ok(Num(&self.0 $op &rhs.0))
+        }
+    };
+}
+
+impl Num {
+    binary_op!(add, checked_add, +);
+    binary_op!(sub, checked_sub, -);
+    binary_op!(mul, checked_mul, *);
+    binary_op!(div, checked_div, /);
+    binary_op!(rem, checked_rem, %);
+
+    pub fn zero() -> Self {
+        Self::from(0)
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+
+    /// `10^exp`, used for splitting and concatenating decimal numbers.
+    pub fn pow10(exp: u32) -> NumResult {
+        (0..exp).try_fold(Self::one(), |acc, _| acc.mul(&Self::from(10)))
+    }
+
+    /// Number of decimal digits, ignoring the sign. Zero has one digit.
+    #[cfg(not(feature = "bigint"))]
+    pub fn digits(&self) -> u32 {
+        self.0
+            .unsigned_abs()
+            .checked_ilog10()
+            .map_or(1, |log| log + 1)
+    }
+
+    /// Number of decimal digits, ignoring the sign. Zero has one digit.
+    #[cfg(feature = "bigint")]
+    pub fn digits(&self) -> u32 {
+        let s = self.to_string();
+        s.trim_start_matches('-').len() as u32
+    }
+
+    /// Divides only if `rhs` is a non-zero exact divisor.
+    pub fn div_exact(&self, rhs: &Num) -> Option<NumResult> {
+        if rhs.is_zero() {
+            return None;
+        }
+        match self.rem(rhs) {
+            Ok(rem) if rem.is_zero() => Some(self.div(rhs)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Bitwise exclusive or, which cannot overflow.
+    #[cfg(not(feature = "bigint"))]
+    pub fn xor(&self, rhs: &Num) -> NumResult {
+        Ok(Num(self.0 ^ rhs.0))
+    }
+
+    /// Bitwise exclusive or, which cannot overflow.
+    #[cfg(feature = "bigint")]
+    pub fn xor(&self, rhs: &Num) -> NumResult {
+        Ok(Num(&self.0 ^ &rhs.0))
+    }
+
+    /// Sums the values, stopping at the first overflow.
+    pub fn try_sum<I: IntoIterator<Item = Num>>(iter: I) -> NumResult {
+        iter.into_iter()
+            .try_fold(Self::zero(), |acc, value| acc.add(&value))
+    }
+}
+
+impl From<i64> for Num {
+    fn from(value: i64) -> Self {
+        Self(value.into())
+    }
+}
+
+impl TryFrom<usize> for Num {
+    type Error = Overflow;
+
+    #[cfg(not(feature = "bigint"))]
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Repr::try_from(value).map(Self).map_err(|_| Overflow {
+            op: "as",
+            lhs: value.to_string(),
+            rhs: "Num".to_string(),
+        })
+    }
+
+    #[cfg(feature = "bigint")]
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self(value.into()))
+    }
+}
+
+impl TryFrom<&Num> for usize {
+    type Error = Overflow;
+
+    fn try_from(value: &Num) -> Result<Self, Self::Error> {
+        #[cfg(not(feature = "bigint"))]
+        let converted = usize::try_from(value.0);
+        #[cfg(feature = "bigint")]
+        let converted = usize::try_from(&value.0);
+
+        converted.map_err(|_| Overflow {
+            op: "as",
+            lhs: value.to_string(),
+            rhs: "usize".to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Num {
+    #[cfg(not(feature = "bigint"))]
+    type Err = std::num::ParseIntError;
+    #[cfg(feature = "bigint")]
+    type Err = num_bigint::ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Repr>().map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(value: i64) -> Num {
+        Num::from(value)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(num(2).add(&num(3)), Ok(num(5)));
+        assert_eq!(num(2).sub(&num(3)), Ok(num(-1)));
+        assert_eq!(num(6).mul(&num(7)), Ok(num(42)));
+        assert_eq!(num(7).div(&num(2)), Ok(num(3)));
+        assert_eq!(num(7).rem(&num(2)), Ok(num(1)));
+        assert_eq!(Num::pow10(3), Ok(num(1000)));
+        assert_eq!(num(6).xor(&num(3)), Ok(num(5)));
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(num(0).digits(), 1);
+        assert_eq!(num(2024).digits(), 4);
+        assert_eq!(num(-99).digits(), 2);
+        assert_eq!(num(9).digits(), 1);
+        assert_eq!(num(10).digits(), 2);
+        assert_eq!(num(i64::MIN).digits(), 19);
+        let max = u64::MAX.to_string().parse::<Num>().unwrap();
+        assert_eq!(max.digits(), 20);
+    }
+
+    #[test]
+    fn test_div_exact() {
+        assert_eq!(num(190).div_exact(&num(19)), Some(Ok(num(10))));
+        assert_eq!(num(190).div_exact(&num(7)), None);
+        assert_eq!(num(190).div_exact(&num(0)), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("10000000000000".parse::<Num>(), Ok(num(10000000000000)));
+        assert!("ten".parse::<Num>().is_err());
+    }
+
+    #[test]
+    fn test_u64_range() {
+        let max = u64::MAX.to_string().parse::<Num>().unwrap();
+        assert_eq!(max.to_string(), "18446744073709551615");
+        assert_eq!(
+            Num::try_from(usize::MAX).unwrap().to_string(),
+            usize::MAX.to_string()
+        );
+        assert_eq!(
+            usize::try_from(&Num::try_from(usize::MAX).unwrap()),
+            Ok(usize::MAX)
+        );
+        assert_eq!(
+            num(i64::MAX).add(&num(1)).map(|sum| sum.to_string()),
+            Ok("9223372036854775808".to_string())
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_checked_overflow() {
+        let max = i128::MAX.to_string().parse::<Num>().unwrap();
+        let err = max.add(&num(1)).unwrap_err();
+        assert_eq!(err.op, "+");
+        assert!(Num::pow10(38).is_ok());
+        assert!(Num::pow10(39).is_err());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_does_not_overflow() {
+        let big = num(i64::MAX).mul(&num(i64::MAX)).unwrap();
+        assert_eq!(big.to_string(), "85070591730234615847396907784232501249");
+        assert_eq!(Num::pow10(30).unwrap().digits(), 31);
+    }
+}
@@ -0,0 +1,187 @@
+//! Uniform reporting of answers, either as the day's own text or as JSON lines.
+
+use crate::answers::{Action, AnswerStore};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format `{s}`, expected `text` or `json`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    pub duration_ns: u128,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+}
+
+impl Record {
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.duration_ns as u64)
+    }
+
+    pub fn with_diagnostic(mut self, diagnostic: impl Into<String>) -> Self {
+        self.diagnostics.push(diagnostic.into());
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("record is always serializable")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reporter {
+    day: u8,
+    format: Format,
+    answers: Option<(RefCell<AnswerStore>, Action)>,
+}
+
+impl Reporter {
+    pub fn new(day: u8, format: Format) -> Self {
+        Self {
+            day,
+            format,
+            answers: None,
+        }
+    }
+
+    /// Checks every emitted record against `store`, or records it there,
+    /// depending on `action`.
+    pub fn with_answers(mut self, store: AnswerStore, action: Action) -> Self {
+        self.answers = Some((RefCell::new(store), action));
+        self
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn record(
+        &self,
+        part: u8,
+        input: &str,
+        answer: impl Display,
+        duration: Duration,
+    ) -> Record {
+        Record {
+            day: self.day,
+            part,
+            input: input.to_string(),
+            answer: answer.to_string(),
+            duration_ns: duration.as_nanos(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Runs `solve` inside a `part` span and records its answer along with
+    /// how long it took.
+    pub fn time<T: Display>(&self, part: u8, input: &str, solve: impl FnOnce() -> T) -> Record {
+        let span = self.span(part, input);
+        let start = Instant::now();
+        let answer = span.in_scope(solve);
+        let record = self.record(part, input, answer, start.elapsed());
+        span.in_scope(
+            || tracing::debug!(answer = %record.answer, duration_ns = record.duration_ns, "solved"),
+        );
+        record
+    }
+
+    pub fn try_time<T: Display, E>(
+        &self,
+        part: u8,
+        input: &str,
+        solve: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Record, E> {
+        let span = self.span(part, input);
+        let start = Instant::now();
+        let answer = span.in_scope(solve)?;
+        let record = self.record(part, input, answer, start.elapsed());
+        span.in_scope(
+            || tracing::debug!(answer = %record.answer, duration_ns = record.duration_ns, "solved"),
+        );
+        Ok(record)
+    }
+
+    fn span(&self, part: u8, input: &str) -> tracing::Span {
+        tracing::info_span!("part", day = self.day, part, input)
+    }
+
+    /// Prints the record as a JSON line, or as the day's own text rendering,
+    /// then [checks](Reporter::check) it.
+    pub fn emit(&self, record: &Record, text: impl FnOnce(&Record) -> String) {
+        match self.format {
+            Format::Text => println!("{}", text(record)),
+            Format::Json => println!("{}", record.to_json()),
+        }
+        self.check(record);
+    }
+
+    /// Verifies or stores the answer in the answer store, exiting the
+    /// process if it is known to be wrong.
+    pub fn check(&self, record: &Record) {
+        let Some((store, action)) = &self.answers else {
+            return;
+        };
+        if let Err(e) = store.borrow_mut().apply(*action, record) {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    /// Prints decorations such as headers, only in text mode.
+    pub fn text(&self, text: impl FnOnce() -> String) {
+        if self.format == Format::Text {
+            println!("{}", text());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("TEXT".parse(), Ok(Format::Text));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_record_to_json() {
+        let reporter = Reporter::new(7, Format::Json);
+        let record = reporter.record(2, "input \"a\"", 3749, Duration::from_nanos(42));
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":7,"part":2,"input":"input \"a\"","answer":"3749","duration_ns":42}"#
+        );
+
+        let record = record.with_diagnostic("brute force");
+        assert!(record
+            .to_json()
+            .ends_with(r#""duration_ns":42,"diagnostics":["brute force"]}"#));
+    }
+}
@@ -0,0 +1,55 @@
+//! A small random number generator for generated inputs: xorshift64*, which
+//! is fast and reproducible by seed but not for anything that needs to be
+//! unpredictable.
+
+const GOLDEN: u64 = 0x9E37_79B9_7F4A_7C15;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// The same `seed` always gives the same numbers. Any seed works.
+    pub fn new(seed: u64) -> Self {
+        // xorshift never leaves a zero state.
+        let state = match seed ^ GOLDEN {
+            0 => GOLDEN,
+            state => state,
+        };
+        Self { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Below `bound`, which must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let take = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..4).map(|_| rng.below(1000)).collect::<Vec<_>>()
+        };
+        assert_eq!(take(7), take(7));
+        assert_ne!(take(7), take(8));
+    }
+
+    #[test]
+    fn test_no_zero_state() {
+        let mut rng = Rng::new(GOLDEN);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+    }
+}
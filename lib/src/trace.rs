@@ -0,0 +1,50 @@
+//! Tracing setup shared by the day binaries.
+//!
+//! Verbosity comes from the `AOC_LOG` environment variable, using the
+//! `EnvFilter` syntax (`AOC_LOG=debug`, `AOC_LOG=aoc_2024_15=trace`, ...).
+//! Only warnings are shown when it is unset. Events are written to stderr so
+//! they never mix with the answers on stdout.
+
+use tracing_subscriber::EnvFilter;
+
+pub const ENV_VAR: &str = "AOC_LOG";
+
+/// Installs the global subscriber. Calling it more than once is harmless.
+pub fn init() {
+    let filter = EnvFilter::try_from_env(ENV_VAR).unwrap_or_else(|_| EnvFilter::new("warn"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// Reports how far a long-running computation got, at `info` level.
+pub fn progress(done: usize, total: usize) {
+    tracing::info!(done, total, percent = percent(done, total), "progress");
+}
+
+fn percent(done: usize, total: usize) -> usize {
+    match total {
+        0 => 100,
+        total => done * 100 / total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(0, 10403), 0);
+        assert_eq!(percent(5000, 10000), 50);
+        assert_eq!(percent(3, 0), 100);
+    }
+
+    #[test]
+    fn test_init_twice() {
+        init();
+        init();
+        progress(1, 2);
+    }
+}